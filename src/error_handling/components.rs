@@ -1,12 +1,69 @@
 use crate::error_handling::error_handling;
+use crate::error_handling::numeric_format::NumericFormat;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Modello di allocazione dei blocchi elaborativi (sommatore, moltiplicatore, unità esponenziale, comparatore)
+/// ai neuroni della rete: un errore su un blocco colpisce solo i neuroni a cui quel blocco è assegnato
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitAllocation {
+    /// un unico insieme di blocchi condiviso da tutta la rete (unità `0`)
+    #[default]
+    Global,
+    /// un insieme di blocchi per ciascun layer (unità = indice del layer)
+    PerLayer,
+    /// un insieme di blocchi per ciascun neurone (unità = indice del neurone, contato su tutta la rete)
+    PerNeuron,
+}
+
+/// Blocchi elaborativi affetti da errore, indicizzati dall'unità a cui appartengono;
+/// le unità assenti non hanno alcun errore
+pub type FaultyUnits = BTreeMap<usize, ArithmeticUnits>;
+
+/// Insieme dei blocchi elaborativi utilizzati da un neurone per aggiornare il proprio stato
+#[derive(Clone,Debug, Copy)]
+pub struct ArithmeticUnits{
+    pub adder: Adder,
+    pub mult: Multiplier,
+    pub exp_unit: ExpUnit,
+    pub cmp: Comparator
+}
+impl Default for ArithmeticUnits {
+    /// Blocchi elaborativi senza alcun errore, con datapath a 64 bit in virgola mobile
+    fn default() -> Self {
+        Self {
+            adder: Adder::new(3,0),
+            mult: Multiplier::new(3,0),
+            exp_unit: ExpUnit::new(3,0),
+            cmp: Comparator::new(3),
+        }
+    }
+}
+impl ArithmeticUnits {
+    /// Ritorna blocchi elaborativi senza alcun errore con il formato numerico specificato
+    pub fn with_format(format: NumericFormat) -> Self {
+        let mut units = Self::default();
+        units.set_format(format);
+        units
+    }
+    /// Setta il formato numerico di tutti i blocchi elaborativi
+    pub fn set_format(&mut self, format: NumericFormat) {
+        self.adder.set_format(format);
+        self.mult.set_format(format);
+        self.exp_unit.set_format(format);
+    }
+}
 /// Struttura rappresentante il componente elaborativo Sommatore in un circuito.
 /// Può essere affetto da errore sugli ingressi o sull'uscita
 #[derive(Clone,Debug, Copy)]
 pub struct Adder{
     /// tipo di errore sull'uscita; ha valore `3` se non c'è errore
     error:i32,
-    /// posizione del bit affetto da errore
-    position: u8,
+    /// formato numerico dei valori in ingresso e in uscita
+    format: NumericFormat,
+    /// maschera dei bit affetti da errore
+    mask: u64,
     /// opzionale tipo di errore sugli ingressi;
     /// l'errore può essere presente su entrambi gli ingressi o uno solamente.
     /// e.g.
@@ -21,8 +78,10 @@ pub struct Adder{
 pub struct Multiplier{
     /// tipo di errore sull'uscita; ha valore `3` se non c'è errore
     error:i32,
-    /// posizione del bit affetto da errore
-    position: u8,
+    /// formato numerico dei valori in ingresso e in uscita
+    format: NumericFormat,
+    /// maschera dei bit affetti da errore
+    mask: u64,
     /// opzionale tipo di errore sugli ingressi;
     /// l'errore può essere presente su entrambi gli ingressi o uno solamente.
     /// e.g.
@@ -31,29 +90,59 @@ pub struct Multiplier{
     /// * stuck-at-0 su entrambi gli ingressi: `(0,0)`,
     input: Option<(i32,i32)>
 }
+/// Struttura rappresentante il componente elaborativo che calcola l'esponenziale (e.g. tramite LUT) in un circuito.
+/// Può essere affetto da errore sull'ingresso o sull'uscita
+#[derive(Clone,Debug, Copy)]
+pub struct ExpUnit{
+    /// tipo di errore sull'uscita; ha valore `3` se non c'è errore
+    error:i32,
+    /// formato numerico dei valori in ingresso e in uscita
+    format: NumericFormat,
+    /// maschera dei bit affetti da errore
+    mask: u64,
+    /// opzionale tipo di errore sull'unico ingresso
+    input: Option<i32>
+}
+/// Struttura rappresentante il componente Comparatore in un circuito, che decide la generazione di un impulso.
+/// Può essere affetto da errore sul suo unico bit d'uscita
+#[derive(Clone,Debug, Copy)]
+pub struct Comparator{
+    /// tipo di errore sull'uscita; ha valore `3` se non c'è errore
+    error:i32
+}
 impl Adder{
-    pub fn new(err: i32,pos: u8)->Self{
+    pub fn new(err: i32,mask: u64)->Self{
         Self{
             error: err,
-            position: pos,
+            format: NumericFormat::Float64,
+            mask,
             input: None
         }
     }
+    /// Setta il formato numerico del datapath: i risultati vengono arrotondati a tale formato
+    /// e gli errori agiscono sui bit della rappresentazione nel formato
+    pub fn set_format(&mut self, format: NumericFormat){
+        self.format = format;
+    }
+    /// Ritorna il formato numerico del datapath
+    pub fn format(&self) -> NumericFormat {
+        self.format
+    }
     /// Funzione equivalente a **`input1 + input2`**, ma aggiunge eventuali errori
     /// sui valori di ingresso o quello d'uscita
     pub fn add(&self, input1:f64, input2:f64)->f64{
         match  self.input{
             None =>{
-                let mut  sum = input1+input2;
-                error_handling::adder_fault(&mut sum, self.error, self.position);
+                let mut  sum = self.format.quantize(input1+input2);
+                error_handling::adder_fault(&mut sum, self.error, self.mask, self.format);
                 sum
             },
             Some((err,err2))=>{
                 let mut inp1 = input1;
                 let mut inp2 = input2;
-                error_handling::adder_fault_input(&mut inp1,err, self.position);
-                error_handling::adder_fault_input(&mut inp2,err2, self.position);
-                inp1+inp2
+                error_handling::adder_fault_input(&mut inp1,err, self.mask, self.format);
+                error_handling::adder_fault_input(&mut inp2,err2, self.mask, self.format);
+                self.format.quantize(inp1+inp2)
             }
         }
 
@@ -64,57 +153,63 @@ impl Adder{
         match  self.input{
             None =>{
                 /* Nessun errore sull'ingresso, possibile errore sull'uscita */
-                let mut  sub = input1-input2;
-                error_handling::adder_fault(&mut sub, self.error, self.position);
+                let mut  sub = self.format.quantize(input1-input2);
+                error_handling::adder_fault(&mut sub, self.error, self.mask, self.format);
                 sub
             },
             Some((err,err2))=>{
                 let mut inp1 = input1;
                 let mut inp2 = input2;
-                error_handling::adder_fault_input(&mut inp1,err, self.position);
-                error_handling::adder_fault_input(&mut inp2,err2, self.position);
-                inp1-inp2
+                error_handling::adder_fault_input(&mut inp1,err, self.mask, self.format);
+                error_handling::adder_fault_input(&mut inp2,err2, self.mask, self.format);
+                self.format.quantize(inp1-inp2)
             }
         }
     }
-    /// Setta i parametri di errore su un bit del valore in uscita
-    pub fn set_params(&mut self, err: i32,pos: u8){
-        self.position = pos;
+    /// Setta i parametri di errore sui bit del valore in uscita
+    pub fn set_params(&mut self, err: i32,mask: u64){
+        self.mask = mask;
         self.error = err;
         /* Se si è aggiunto un errore in uscita, forziamo l'assenza di errori in ingresso*/
         self.input=None;
     }
-    /// Setta i parametri di errore su un bit di uno o entrambi i valori in ingresso
-    pub fn set_params_input(&mut self, pos: u8, err_input1: i32,err_input2: i32){
-        self.position = pos;
+    /// Setta i parametri di errore sui bit di uno o entrambi i valori in ingresso
+    pub fn set_params_input(&mut self, mask: u64, err_input1: i32,err_input2: i32){
+        self.mask = mask;
         /* Se si è aggiunto un errore in ingresso, forziamo l'assenza di errori in uscita*/
         self.error = 3;
         self.input = Some((err_input1, err_input2));
     }
 }
 impl Multiplier{
-    pub fn new(err: i32,pos: u8)->Self{
+    pub fn new(err: i32,mask: u64)->Self{
         Self{
             error: err,
-            position: pos,
+            format: NumericFormat::Float64,
+            mask,
             input: None
         }
     }
+    /// Setta il formato numerico del datapath: i risultati vengono arrotondati a tale formato
+    /// e gli errori agiscono sui bit della rappresentazione nel formato
+    pub fn set_format(&mut self, format: NumericFormat){
+        self.format = format;
+    }
     /// Funzione equivalente a **`input1 * input2`**, ma aggiunge eventuali errori
     /// sui valori di ingresso o quello d'uscita
     pub fn mul(&self, input1:f64, input2:f64)->f64{
         match  self.input{
             None =>{
-                let mut  mul = input1*input2;
-                error_handling::mult_fault(&mut mul, self.error, self.position);
+                let mut  mul = self.format.quantize(input1*input2);
+                error_handling::mult_fault(&mut mul, self.error, self.mask, self.format);
                 mul
             },
             Some((err,err2))=>{
                 let mut inp1 = input1;
                 let mut inp2 = input2;
-                error_handling::mult_fault_input(&mut inp1,err, self.position);
-                error_handling::mult_fault_input(&mut inp2,err2, self.position);
-                inp1*inp2
+                error_handling::mult_fault_input(&mut inp1,err, self.mask, self.format);
+                error_handling::mult_fault_input(&mut inp2,err2, self.mask, self.format);
+                self.format.quantize(inp1*inp2)
             }
         }
 
@@ -124,38 +219,93 @@ impl Multiplier{
     pub fn div(&self, input1:f64, input2:f64)->f64{
         match  self.input{
             None =>{
-                let mut  div = input1/input2;
-                error_handling::mult_fault(&mut div, self.error, self.position);
+                let mut  div = self.format.quantize(input1/input2);
+                error_handling::mult_fault(&mut div, self.error, self.mask, self.format);
                 div
             },
             Some((err,err2))=>{
                 let mut inp1 = input1;
                 let mut inp2 = input2;
-                error_handling::mult_fault_input(&mut inp1,err, self.position);
-                error_handling::mult_fault_input(&mut inp2,err2, self.position);
-                inp1/inp2
+                error_handling::mult_fault_input(&mut inp1,err, self.mask, self.format);
+                error_handling::mult_fault_input(&mut inp2,err2, self.mask, self.format);
+                self.format.quantize(inp1/inp2)
             }
         }
     }
-    /// Setta i parametri di errore su un bit del valore in uscita
-    pub fn set_params(&mut self, err: i32,pos: u8){
-        self.position = pos;
+    /// Setta i parametri di errore sui bit del valore in uscita
+    pub fn set_params(&mut self, err: i32,mask: u64){
+        self.mask = mask;
         self.error = err;
         /* Forziamo l'assenza di errori in input*/
         self.input=None;
     }
-    /// Setta i parametri di errore su un bit di uno o entrambi i valori in ingresso
-    pub fn set_params_input(&mut self, pos: u8, err_input1: i32,err_input2: i32){
-        self.position = pos;
+    /// Setta i parametri di errore sui bit di uno o entrambi i valori in ingresso
+    pub fn set_params_input(&mut self, mask: u64, err_input1: i32,err_input2: i32){
+        self.mask = mask;
         self.error = 3;
         self.input = Some((err_input1, err_input2));
     }
 }
-
-
-
-
-
-
-
-
+impl ExpUnit{
+    pub fn new(err: i32,mask: u64)->Self{
+        Self{
+            error: err,
+            format: NumericFormat::Float64,
+            mask,
+            input: None
+        }
+    }
+    /// Setta il formato numerico del datapath: i risultati vengono arrotondati a tale formato
+    /// e gli errori agiscono sui bit della rappresentazione nel formato
+    pub fn set_format(&mut self, format: NumericFormat){
+        self.format = format;
+    }
+    /// Funzione equivalente a **`input.exp()`**, ma aggiunge eventuali errori
+    /// sul valore di ingresso o quello d'uscita
+    pub fn exp(&self, input:f64)->f64{
+        match  self.input{
+            None =>{
+                let mut  exp = self.format.quantize(input.exp());
+                error_handling::exp_fault(&mut exp, self.error, self.mask, self.format);
+                exp
+            },
+            Some(err)=>{
+                let mut inp = input;
+                error_handling::exp_fault_input(&mut inp,err, self.mask, self.format);
+                self.format.quantize(inp.exp())
+            }
+        }
+    }
+    /// Setta i parametri di errore sui bit del valore in uscita
+    pub fn set_params(&mut self, err: i32,mask: u64){
+        self.mask = mask;
+        self.error = err;
+        /* Forziamo l'assenza di errori in input*/
+        self.input=None;
+    }
+    /// Setta i parametri di errore sui bit del valore in ingresso
+    pub fn set_params_input(&mut self, mask: u64, err_input: i32){
+        self.mask = mask;
+        self.error = 3;
+        self.input = Some(err_input);
+    }
+}
+impl Comparator{
+    pub fn new(err: i32)->Self{
+        Self{
+            error: err
+        }
+    }
+    /// Funzione equivalente a **`input1 > input2`**, ma aggiunge l'eventuale errore sul bit d'uscita
+    pub fn gt(&self, input1:f64, input2:f64)->bool{
+        error_handling::comparator_fault(input1 > input2, self.error)
+    }
+    /// Funzione equivalente a **`input1 >= input2`**, ma aggiunge l'eventuale errore sul bit d'uscita
+    pub fn ge(&self, input1:f64, input2:f64)->bool{
+        error_handling::comparator_fault(input1 >= input2, self.error)
+    }
+    /// Setta il tipo di errore sul bit in uscita
+    pub fn set_params(&mut self, err: i32){
+        self.error = err;
+    }
+}
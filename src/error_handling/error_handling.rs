@@ -1,5 +1,7 @@
 use crate::error_handling::error_handling::ErrorType::{Flip, Stuck0, Stuck1};
 use crate::snn::neuron::Neuron;
+use crate::error_handling::numeric_format::NumericFormat;
+use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone)]
 pub enum ErrorType{
@@ -9,56 +11,139 @@ pub enum ErrorType{
     None
 }
 
+/// Forma dell'errore iniettato in una parola: i bit affetti vengono tutti forzati (stuck-at-X) o invertiti (bit-flip)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Upset {
+    /// un solo bit, scelto a caso
+    #[default]
+    Single,
+    /// `N` bit adiacenti, a partire da un bit scelto a caso
+    Adjacent(u8),
+    /// maschera arbitraria di bit, sempre la stessa (su file in esadecimale, come la maschera di `Fault`)
+    Mask(#[serde(with = "crate::error_handling::fault::hex_mask")] u64),
+}
+impl Upset {
+/// Ritorna il numero di bit adiacenti coperti dall'errore a partire dal bit meno significativo colpito
+/// (per una maschera arbitraria, la distanza tra il bit più significativo e il meno significativo)
+    pub fn span(&self) -> u8 {
+        match *self {
+            Upset::Single => 1,
+            Upset::Adjacent(n) => n,
+            Upset::Mask(mask) => (64 - mask.leading_zeros() - mask.trailing_zeros()) as u8,
+        }
+    }
+/// Ritorna la maschera dei bit affetti quando il bit meno significativo colpito è `position`
+/// (ignorato per una maschera arbitraria)
+    pub fn mask(&self, position: u8) -> u64 {
+        match *self {
+            Upset::Single => 1u64 << position,
+            Upset::Adjacent(n) => (u64::MAX >> (64 - n as u32)) << position,
+            Upset::Mask(mask) => mask,
+        }
+    }
+/// Ritorna `true` se l'errore è contenuto in una parola di `width` bit
+    pub fn fits(&self, width: u8) -> bool {
+        match *self {
+            Upset::Single => true,
+            Upset::Adjacent(n) => n >= 1 && n <= width,
+            Upset::Mask(mask) => mask != 0 && (width == 64 || mask >> width == 0),
+        }
+    }
+}
+
+/// Struttura che rappresenta un errore stuck-at-X su uno o più bit di un registro
+/// (e.g. potenziale di membrana), che il neurone deve ricordare per tutta la durata dell'inferenza
+#[derive(Clone, Debug)]
+pub struct ErrorBit{
+    error_type:u8, //0/1
+    /// maschera dei bit bloccati
+    mask:u64,
+    /// formato numerico del registro, utilizzato da `force_value`
+    format: NumericFormat,
+}
+impl ErrorBit {
+/// Ritorna un nuovo **ErrorBit*
+/// # Argomenti
+/// * `error_type` - valore a cui i bit sono bloccati *(0/1)*
+/// * `mask` - maschera dei bit bloccati
+    pub fn new(error_type: u8, mask: u64) -> Self {
+        Self { error_type, mask, format: NumericFormat::Float64 }
+    }
+/// Setta il formato numerico in cui è memorizzato il registro bloccato
+    pub fn with_format(mut self, format: NumericFormat) -> Self {
+        self.format = format;
+        self
+    }
+/// Ritorna il valore del registro con i bit bloccati forzati al loro valore
+    pub fn force(&self, value: u64) -> u64 {
+        match self.error_type {
+            /* stuck-at-0 */
+            0 => unset_bits(value, self.mask),
+            /* stuck-at-1 */
+            1 => set_bits(value, self.mask),
+            _ => value
+        }
+    }
+/// Ritorna il valore del registro numerico con i bit bloccati forzati al loro valore,
+/// secondo il formato numerico del registro
+    pub fn force_value(&self, value: f64) -> f64 {
+        self.format.decode(self.force(self.format.encode(value)))
+    }
+}
+
 const ERROR_TABLE: [ErrorType; 4] = [ErrorType::Stuck0, ErrorType::Stuck1, ErrorType::Flip, ErrorType::None];
-/// Ritorna il valore della variabile con il bit modificato
+/// Ritorna il valore della variabile con i bit modificati
 /// # Argomenti
-/// * `variable` - variabile con il bit da modificare
-/// * `error` - ErrorType che indica il tipo di errore sul bit
-/// * `position` - posizione del bit da modificare nella variabile
-fn embed_error(variable:f64, error:ErrorType, position: u8)->f64{
-    let mut bit_value:u64=variable.to_bits();
-    bit_value=match error {
+/// * `variable` - variabile con i bit da modificare
+/// * `error` - ErrorType che indica il tipo di errore sui bit
+/// * `mask` - maschera dei bit da modificare nella variabile
+/// * `format` - formato numerico in cui è memorizzata la variabile
+fn embed_error(variable:f64, error:ErrorType, mask: u64, format: NumericFormat)->f64{
+    format.decode(embed_error_bits(format.encode(variable), error, mask))
+}
+/// Ritorna il valore del registro intero con i bit modificati
+/// # Argomenti
+/// * `bit_value` - registro con i bit da modificare
+/// * `error` - ErrorType che indica il tipo di errore sui bit
+/// * `mask` - maschera dei bit da modificare nel registro
+fn embed_error_bits(bit_value:u64, error:ErrorType, mask: u64)->u64{
+    match error {
         ErrorType::None => bit_value,
-        Stuck0 => unset_bit(bit_value, position),
-        Stuck1 => set_bit(bit_value, position),
-        Flip => flip_bit(bit_value, position),
-    };
-
-    f64::from_bits(bit_value)
+        Stuck0 => unset_bits(bit_value, mask),
+        Stuck1 => set_bits(bit_value, mask),
+        Flip => flip_bits(bit_value, mask),
+    }
 }
 
 /**
-Setta a 1 il bit in posizione *position* di *value*
+Setta a 1 i bit di *value* indicati da *bit_mask* (maschera del tipo 0011000)
  */
-fn set_bit(value:u64, position:u8)->u64{
-    /* maschera del tipo 0001000 */
-    let bit_mask=1u64<<position;
+fn set_bits(value:u64, bit_mask:u64)->u64{
     value | bit_mask
 }
 /**
-Setta a 0 il bit in posizione *position* di *value*
+Setta a 0 i bit di *value* indicati da *bit_mask*
  */
-fn unset_bit(value:u64, position:u8)->u64{
-    /* maschera del tipo 1110111 */
-    let bit_mask=!(1u64<<position);
-    value & bit_mask
+fn unset_bits(value:u64, bit_mask:u64)->u64{
+    /* maschera del tipo 1100111 */
+    value & !bit_mask
 }
 /**
-Inverte il bit in posizione *position* di *value*
+Inverte i bit di *value* indicati da *bit_mask*
  */
-fn flip_bit(value:u64, position:u8)->u64{
-    /* maschera del tipo 0001000 */
-    let bit_mask=1u64<<position;
+fn flip_bits(value:u64, bit_mask:u64)->u64{
     value ^ bit_mask
 }
 /// Inserisce un nuovo errore sul potenziale di membrana. Che sia transitorio o no, il valore deve essere settato una sola volta
 /// # Argomenti
 /// * `neuron` - neurone il cui potenziale di soglia contiene l'errore
 /// * `error_type` - tipo di errore da inserire
-/// * `position` - posizione del bit affetto da errore
-pub fn threshold_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, position:u8){
+/// * `mask` - maschera dei bit affetti da errore
+/// * `format` - formato numerico del datapath
+pub fn threshold_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64, format: NumericFormat){
     /* calcolo del nuovo valore */
-    let new_threshold = embed_error(neuron.get_th(), ERROR_TABLE[error_type as usize], position);
+    let new_threshold = embed_error(neuron.get_th(), ERROR_TABLE[error_type as usize], mask, format);
 
     neuron.set_th(new_threshold);
 
@@ -69,41 +154,108 @@ pub fn threshold_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32,
 /// # Argomenti
 /// * `neuron` - neurone il cui potenziale di soglia contiene l'errore
 /// * `error_type` - tipo di errore da inserire
-/// * `position` - posizione del bit affetto da errore
-pub fn membrane_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, position: u8){
+/// * `mask` - maschera dei bit affetti da errore
+/// * `format` - formato numerico del datapath
+pub fn membrane_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64, format: NumericFormat){
     match error_type {
         // stuck-at-X
-        0|1=>{neuron.set_membrane_error(error_type as u8,position);},
+        0|1=>{neuron.set_membrane_error(error_type as u8,mask, format);},
         // transient
-        2=>{let new_mem = embed_error(neuron.get_mem(), ERROR_TABLE[error_type as usize], position);
-            neuron.set_mem(new_mem);},
+        2=>{membrane_upset(neuron, error_type, mask, format);},
         _=>{}
     }
 }
+/// Modifica una sola volta il valore del potenziale di membrana con l'errore specificato, anche se stuck-at-X:
+/// il valore corretto viene ripristinato alla successiva scrittura del registro.
+/// Utilizzata per gli errori transitori e per le attivazioni degli errori intermittenti
+pub fn membrane_upset<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64, format: NumericFormat){
+    let new_mem = embed_error(neuron.get_mem(), ERROR_TABLE[error_type as usize], mask, format);
+    neuron.set_mem(new_mem);
+}
+/// Inserisce un nuovo errore sul contatore del periodo refrattario.
+/// Come per il potenziale di membrana, un errore transitorio modifica il valore una volta sola,
+/// mentre nel caso stuck-at-X il neurone deve ricordarsi la presenza dell'errore
+/// # Argomenti
+/// * `neuron` - neurone il cui contatore contiene l'errore
+/// * `error_type` - tipo di errore da inserire
+/// * `mask` - maschera dei bit affetti da errore
+pub fn refractory_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64){
+    match error_type {
+        // stuck-at-X
+        0|1=>{neuron.set_refractory_error(error_type as u8,mask);},
+        // transient
+        2=>{refractory_upset(neuron, error_type, mask);},
+        _=>{}
+    }
+}
+/// Modifica una sola volta il valore del contatore del periodo refrattario con l'errore specificato, anche se stuck-at-X
+/// (errori transitori e attivazioni degli errori intermittenti)
+pub fn refractory_upset<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64){
+    let new_counter = embed_error_bits(neuron.get_refractory(), ERROR_TABLE[error_type as usize], mask);
+    neuron.set_refractory(new_counter);
+}
+/// Inserisce un nuovo errore sulla variabile di stato ausiliaria del neurone (e.g. `u` per Izhikevich).
+/// Come per il potenziale di membrana, nel caso stuck-at-X il neurone deve ricordarsi la presenza dell'errore
+/// # Argomenti
+/// * `neuron` - neurone la cui variabile ausiliaria contiene l'errore
+/// * `error_type` - tipo di errore da inserire
+/// * `mask` - maschera dei bit affetti da errore
+/// * `format` - formato numerico del datapath
+pub fn aux_fault<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64, format: NumericFormat){
+    match error_type {
+        // stuck-at-X
+        0|1=>{neuron.set_aux_error(error_type as u8,mask, format);},
+        // transient
+        2=>{aux_upset(neuron, error_type, mask, format);},
+        _=>{}
+    }
+}
+/// Modifica una sola volta il valore della variabile di stato ausiliaria con l'errore specificato, anche se stuck-at-X
+/// (errori transitori e attivazioni degli errori intermittenti)
+pub fn aux_upset<N: Neuron+Clone+'static>(neuron: &mut N, error_type: i32, mask: u64, format: NumericFormat){
+    let new_aux = embed_error(neuron.get_aux(), ERROR_TABLE[error_type as usize], mask, format);
+    neuron.set_aux(new_aux);
+}
 /// Inserisce un nuovo errore sul un peso. Che sia transitorio o no, il valore deve essere settato una sola volta
 /// # Argomenti
 /// * `weight` - reference al peso affetto da errore
 /// * `error_type` - tipo di errore da inserire
-/// * `position` - posizione del bit affetto da errore
-pub fn weight_fault(weight: &mut f64, error_type: i32, position: u8){
+/// * `mask` - maschera dei bit affetti da errore
+/// * `format` - formato numerico del datapath
+pub fn weight_fault(weight: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
 
-    let new_weight = embed_error(*weight, ERROR_TABLE[error_type as usize], position);
+    let new_weight = embed_error(*weight, ERROR_TABLE[error_type as usize], mask, format);
 
     *weight = new_weight;
 }
-pub fn adder_fault(sum: &mut f64, error_type: i32, position: u8){
-    let new_sum = embed_error(*sum, ERROR_TABLE[error_type as usize], position);
+pub fn adder_fault(sum: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
+    let new_sum = embed_error(*sum, ERROR_TABLE[error_type as usize], mask, format);
     *sum = new_sum;
 }
-pub fn adder_fault_input(input: &mut f64, error_type: i32, position: u8){
-    let new_input = embed_error(*input, ERROR_TABLE[error_type as usize], position);
+pub fn adder_fault_input(input: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
+    let new_input = embed_error(*input, ERROR_TABLE[error_type as usize], mask, format);
     *input = new_input;
 }
-pub fn mult_fault(sum: &mut f64, error_type: i32, position: u8){
-    let new_sum = embed_error(*sum, ERROR_TABLE[error_type as usize], position);
+pub fn mult_fault(sum: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
+    let new_sum = embed_error(*sum, ERROR_TABLE[error_type as usize], mask, format);
     *sum = new_sum;
 }
-pub fn mult_fault_input(input: &mut f64, error_type: i32, position: u8){
-    let new_input = embed_error(*input, ERROR_TABLE[error_type as usize], position);
+pub fn mult_fault_input(input: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
+    let new_input = embed_error(*input, ERROR_TABLE[error_type as usize], mask, format);
+    *input = new_input;
+}
+pub fn exp_fault(exp: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
+    let new_exp = embed_error(*exp, ERROR_TABLE[error_type as usize], mask, format);
+    *exp = new_exp;
+}
+pub fn exp_fault_input(input: &mut f64, error_type: i32, mask: u64, format: NumericFormat){
+    let new_input = embed_error(*input, ERROR_TABLE[error_type as usize], mask, format);
     *input = new_input;
-}
\ No newline at end of file
+}
+/// Ritorna il bit d'uscita del comparatore con l'eventuale errore
+/// # Argomenti
+/// * `output` - risultato corretto del confronto
+/// * `error_type` - tipo di errore sul bit d'uscita
+pub fn comparator_fault(output: bool, error_type: i32)->bool{
+    embed_error_bits(output as u64, ERROR_TABLE[error_type as usize], 1) != 0
+}
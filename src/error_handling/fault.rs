@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::snn::network_file::{FileFormat, NetworkFileError};
+use crate::snn::AccumulationMode;
+use crate::error_handling::components::UnitAllocation;
+use crate::error_handling::error_handling::Upset;
+use crate::error_handling::numeric_format::{BitField, NumericFormat};
+
+/// Descrizione completa di un singolo errore da iniettare nella rete: identifica il componente, il bersaglio
+/// e i bit affetti, in modo che lo stesso errore possa essere iniettato più volte in modo riproducibile
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Fault {
+    /// componente affetto da errore (stessi indici di `SnnBuilder::build()`)
+    pub component: i32,
+    /// tipo di errore: `0` stuck-at-0, `1` stuck-at-1, `2` bit-flip transitorio
+    pub error_type: i32,
+    /// layer del neurone scelto; per i blocchi elaborativi, un neurone assegnato all'unità affetta
+    pub layer: usize,
+    /// neurone scelto all'interno del layer
+    pub neuron: usize,
+    /// indice del peso affetto (neurone sorgente), solo per gli errori sui pesi
+    pub weight: Option<usize>,
+    /// maschera dei bit affetti da errore; su file viene scritta in esadecimale (e.g. `"0x8000000000000000"`),
+    /// dato che TOML non rappresenta interi oltre `i64::MAX`
+    #[serde(with = "hex_mask")]
+    pub mask: u64,
+    /// istante iniziale di un errore transitorio; `None` se scelto casualmente da `process()`
+    /// (o se l'errore non è transitorio)
+    pub instant: Option<u64>,
+    /// errori sugli ingressi dei blocchi elaborativi (`3` se l'ingresso non è affetto)
+    pub input_errors: (i32, i32),
+}
+
+/// Configurazione del builder che determina come vengono scelti e iniettati gli errori
+/// (stessi valori predefiniti di `SnnBuilder::new()`), applicata con `SnnBuilder::set_injection_config()`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InjectionConfig {
+    /// formato numerico del datapath
+    pub format: NumericFormat,
+    /// campi della rappresentazione a cui è ristretta l'iniezione (vuoto per qualsiasi bit)
+    pub fields: Vec<BitField>,
+    /// forma degli errori iniettati
+    pub upset: Upset,
+    /// numero di istanti in cui un blocco elaborativo resta affetto da un errore transitorio
+    pub transient_duration: u64,
+    /// probabilità di attivazione in ciascun istante degli errori intermittenti; `None` per errori permanenti e transitori
+    pub intermittent: Option<f64>,
+    /// numero di errori iniettati in ciascuna inferenza
+    pub fault_count: usize,
+    /// tipi di errore tra cui viene scelto casualmente ciascun errore dell'insieme; vuoto per usare il solo tipo selezionato
+    pub error_types: Vec<i32>,
+    /// modello di allocazione dei blocchi elaborativi ai neuroni
+    pub allocation: UnitAllocation,
+    /// accumulazioni delle somme pesate che passano dal sommatore
+    pub accumulation: AccumulationMode,
+}
+
+impl Default for InjectionConfig {
+    fn default() -> Self {
+        Self {
+            format: NumericFormat::default(),
+            fields: vec![],
+            upset: Upset::default(),
+            transient_duration: 1,
+            intermittent: None,
+            fault_count: 1,
+            error_types: vec![],
+            allocation: UnitAllocation::default(),
+            accumulation: AccumulationMode::default(),
+        }
+    }
+}
+
+/// Errori iniettati in una singola inferenza di una campagna
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultRun {
+    /// indice dell'inferenza nella campagna in cui gli errori sono stati iniettati
+    pub run: usize,
+    /// insieme di errori iniettati nell'inferenza
+    pub faults: Vec<Fault>,
+}
+
+/// Lista esatta degli errori iniettati da una campagna, inferenza per inferenza: salvata su file, permette
+/// di ripetere la campagna con gli stessi errori (e.g. su una versione modificata o irrobustita della rete)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FaultList {
+    /// seme della campagna: le inferenze ripetute usano gli stessi semi, e quindi gli stessi istanti di attivazione
+    /// degli errori intermittenti; su file viene scritto come stringa decimale, dato che TOML non rappresenta interi
+    /// oltre `i64::MAX`. `None` per le liste salvate senza seme
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "decimal_seed")]
+    pub seed: Option<u64>,
+    /// configurazione dell'iniezione con cui gli errori sono stati scelti e iniettati (formato, forma degli errori,
+    /// durata dei transitori, ...), necessaria per ripetere la campagna con la stessa semantica
+    #[serde(default)]
+    pub config: InjectionConfig,
+    pub runs: Vec<FaultRun>,
+}
+
+impl FaultList {
+/// Legge la lista di errori dal file `path`; il formato (TOML o JSON) viene dedotto dall'estensione del file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<FaultList, NetworkFileError> {
+        let path = path.as_ref();
+        let format = FileFormat::from_path(path)?;
+        let content = fs::read_to_string(path)?;
+        match format {
+            FileFormat::Toml => toml::from_str(&content).map_err(|e| NetworkFileError::Parse(e.to_string())),
+            FileFormat::Json => serde_json::from_str(&content).map_err(|e| NetworkFileError::Parse(e.to_string())),
+        }
+    }
+/// Salva la lista di errori nel file `path`; il formato (TOML o JSON) viene dedotto dall'estensione del file
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), NetworkFileError> {
+        let path = path.as_ref();
+        let format = FileFormat::from_path(path)?;
+        let content = match format {
+            FileFormat::Toml => toml::to_string(self).map_err(|e| NetworkFileError::Serialize(e.to_string()))?,
+            FileFormat::Json => serde_json::to_string_pretty(self).map_err(|e| NetworkFileError::Serialize(e.to_string()))?,
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// (De)serializzazione del seme opzionale come stringa decimale
+mod decimal_seed {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(seed: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match seed {
+            Some(seed) => serializer.serialize_str(&seed.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+        let seed = String::deserialize(deserializer)?;
+        seed.parse::<u64>().map(Some).map_err(|e| D::Error::custom(format!("invalid seed {}: {}", seed, e)))
+    }
+}
+
+/// (De)serializzazione della maschera di bit come stringa esadecimale
+pub(crate) mod hex_mask {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(mask: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", mask))
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let mask = String::deserialize(deserializer)?;
+        let digits = mask.strip_prefix("0x").unwrap_or(&mask);
+        u64::from_str_radix(digits, 16).map_err(|e| D::Error::custom(format!("invalid mask {}: {}", mask, e)))
+    }
+}
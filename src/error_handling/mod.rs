@@ -1,2 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod error_handling;
 pub mod components;
+pub mod numeric_format;
+pub mod fault;
+pub mod sampling;
@@ -0,0 +1,306 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+/// Comportamento di un valore a virgola fissa che esce dall'intervallo rappresentabile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Overflow {
+    /// il valore viene limitato al massimo (o minimo) rappresentabile
+    #[default]
+    Saturate,
+    /// vengono mantenuti solo i bit meno significativi (complemento a 2)
+    Wrap,
+}
+
+/// Campo della rappresentazione di un valore a cui appartiene un bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitField {
+    /// bit di segno (formati a virgola mobile e a virgola fissa)
+    Sign,
+    /// bit dell'esponente di un formato a virgola mobile
+    Exponent,
+    /// bit della mantissa di un formato a virgola mobile
+    Mantissa,
+    /// bit della parte intera di un formato a virgola fissa
+    Integer,
+    /// bit della parte frazionaria di un formato a virgola fissa
+    Fraction,
+}
+
+impl Display for BitField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BitField::Sign => write!(f, "Sign"),
+            BitField::Exponent => write!(f, "Exponent"),
+            BitField::Mantissa => write!(f, "Mantissa"),
+            BitField::Integer => write!(f, "Integer"),
+            BitField::Fraction => write!(f, "Fraction"),
+        }
+    }
+}
+
+/// Formato numerico del datapath simulato: determina come i valori vengono memorizzati nei registri,
+/// come vengono arrotondati dopo ogni operazione dei blocchi elaborativi e su quali bit agiscono gli errori
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumericFormat {
+    /// valori IEEE-754 a 64 bit (nessuna quantizzazione)
+    #[default]
+    Float64,
+    /// valori IEEE-754 a 32 bit (singola precisione)
+    Float32,
+    /// valori *brain floating point* a 16 bit: segno ed esponente come `Float32`, mantissa ridotta a 7 bit
+    BFloat16,
+    /// valori a virgola fissa **Qm.n** in complemento a 2, su `1 + int_bits + frac_bits` bit (bit di segno compreso)
+    Fixed {
+        /// numero di bit della parte intera (`m`), escluso il bit di segno
+        int_bits: u8,
+        /// numero di bit della parte frazionaria (`n`)
+        frac_bits: u8,
+        /// comportamento in caso di overflow
+        #[serde(default)]
+        overflow: Overflow,
+    },
+}
+
+impl NumericFormat {
+/// Ritorna un formato a virgola fissa Qm.n.
+/// La larghezza totale `1 + int_bits + frac_bits` non può superare 64 bit
+    pub fn fixed(int_bits: u8, frac_bits: u8, overflow: Overflow) -> Self {
+        let format = NumericFormat::Fixed { int_bits, frac_bits, overflow };
+        assert!(format.is_valid(), "Error: Q{}.{} does not fit in 64 bits", int_bits, frac_bits);
+        format
+    }
+/// Ritorna `true` se un registro in questo formato è contenuto in 64 bit; un formato a virgola fissa letto da file
+/// (o costruito senza `fixed()`) potrebbe non esserlo, e viene rifiutato dal builder con `BuildError::InvalidFormat`
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            NumericFormat::Fixed { int_bits, frac_bits, .. } => 1 + int_bits as u32 + frac_bits as u32 <= 64,
+            _ => true,
+        }
+    }
+/// Ritorna il numero di bit di un registro in questo formato, i.e. le posizioni valide per un errore sono `0..width()`
+/// (solo per i formati validi, vedi `is_valid()`)
+    pub fn width(&self) -> u8 {
+        match *self {
+            NumericFormat::Float64 => 64,
+            NumericFormat::Float32 => 32,
+            NumericFormat::BFloat16 => 16,
+            NumericFormat::Fixed { int_bits, frac_bits, .. } => 1u8.saturating_add(int_bits).saturating_add(frac_bits),
+        }
+    }
+/// Ritorna il campo della rappresentazione a cui appartiene il bit in posizione `position`
+    pub fn field_of(&self, position: u8) -> BitField {
+        let width = self.width();
+        assert!(position < width, "Error: bit {} out of range for a {}-bit format", position, width);
+        if position == width - 1 {
+            return BitField::Sign;
+        }
+        match *self {
+            NumericFormat::Fixed { frac_bits, .. } =>
+                if position < frac_bits { BitField::Fraction } else { BitField::Integer },
+            _ => if position < self.mantissa_bits() { BitField::Mantissa } else { BitField::Exponent },
+        }
+    }
+/// Ritorna le posizioni dei bit che appartengono a uno dei campi `fields`
+    pub fn positions_in(&self, fields: &[BitField]) -> Vec<u8> {
+        (0..self.width()).filter(|&position| fields.contains(&self.field_of(position))).collect()
+    }
+/// Ritorna il numero di bit della mantissa (0 per la virgola fissa)
+    fn mantissa_bits(&self) -> u8 {
+        match *self {
+            NumericFormat::Float64 => 52,
+            NumericFormat::Float32 => 23,
+            NumericFormat::BFloat16 => 7,
+            NumericFormat::Fixed { .. } => 0,
+        }
+    }
+/// Ritorna il contenuto del registro che memorizza `value` in questo formato (nei `width()` bit meno significativi)
+    pub fn encode(&self, value: f64) -> u64 {
+        match *self {
+            NumericFormat::Float64 => value.to_bits(),
+            NumericFormat::Float32 => (value as f32).to_bits() as u64,
+            NumericFormat::BFloat16 => {
+                let bits = (value as f32).to_bits();
+                if (value as f32).is_nan() {
+                    /* NaN silenzioso, evita che il troncamento lo trasformi in infinito */
+                    return ((bits >> 16) | 0x0040) as u64;
+                }
+                /* arrotondamento al più vicino (pari in caso di parità) dei 16 bit meno significativi */
+                let rounding = 0x7FFF + ((bits >> 16) & 1);
+                (bits.wrapping_add(rounding) >> 16) as u64
+            }
+            NumericFormat::Fixed { frac_bits, overflow, .. } => {
+                let width = self.width() as u32;
+                let mask = if width == 64 { u64::MAX } else { (1u64 << width) - 1 };
+                /* i valori non rappresentabili (NaN) vengono memorizzati come 0 */
+                let scaled = if value.is_nan() { 0.0 } else { (value * 2f64.powi(frac_bits as i32)).round() };
+                let raw = match overflow {
+                    Overflow::Saturate => {
+                        let max = (1i128 << (width - 1)) - 1;
+                        let min = -(1i128 << (width - 1));
+                        (scaled as i128).clamp(min, max)
+                    }
+                    /* il cast a i128 satura solo oltre 2^127, dopo di che si tengono i bit meno significativi */
+                    Overflow::Wrap => scaled as i128,
+                };
+                (raw as u64) & mask
+            }
+        }
+    }
+/// Ritorna il valore memorizzato nel registro `bits` in questo formato
+    pub fn decode(&self, bits: u64) -> f64 {
+        match *self {
+            NumericFormat::Float64 => f64::from_bits(bits),
+            NumericFormat::Float32 => f32::from_bits(bits as u32) as f64,
+            NumericFormat::BFloat16 => f32::from_bits(((bits as u32) & 0xFFFF) << 16) as f64,
+            NumericFormat::Fixed { frac_bits, .. } => {
+                let width = self.width() as u32;
+                /* estensione del segno dal bit width-1 */
+                let shift = 64 - width;
+                let raw = ((bits << shift) as i64) >> shift;
+                raw as f64 / 2f64.powi(frac_bits as i32)
+            }
+        }
+    }
+/// Ritorna `value` arrotondato al valore rappresentabile più vicino in questo formato
+    pub fn quantize(&self, value: f64) -> f64 {
+        match self {
+            NumericFormat::Float64 => value,
+            _ => self.decode(self.encode(value)),
+        }
+    }
+}
+
+impl Display for NumericFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumericFormat::Float64 => write!(f, "f64"),
+            NumericFormat::Float32 => write!(f, "f32"),
+            NumericFormat::BFloat16 => write!(f, "bf16"),
+            NumericFormat::Fixed { int_bits, frac_bits, overflow } => write!(f, "Q{}.{} ({:?})", int_bits, frac_bits, overflow),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float64_round_trip() {
+        let format = NumericFormat::Float64;
+        for value in [0.0, -0.0, 0.1, -3.75, 1e300, f64::MIN_POSITIVE, f64::INFINITY] {
+            assert_eq!(format.encode(value), value.to_bits());
+            assert_eq!(format.decode(format.encode(value)).to_bits(), value.to_bits());
+            assert_eq!(format.quantize(value).to_bits(), value.to_bits());
+        }
+        assert!(format.quantize(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn float32_round_trip() {
+        let format = NumericFormat::Float32;
+        assert_eq!(format.encode(1.0), 0x3F80_0000);
+        assert_eq!(format.encode(-2.0), 0xC000_0000);
+        assert_eq!(format.decode(0x3F80_0000), 1.0);
+        assert_eq!(format.quantize(0.1), 0.1f32 as f64);
+        /* i valori rappresentabili in f32 non cambiano */
+        assert_eq!(format.quantize(0.375), 0.375);
+        assert_eq!(format.quantize(1e300), f64::INFINITY);
+        assert!(format.quantize(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn bfloat16_round_trip() {
+        let format = NumericFormat::BFloat16;
+        assert_eq!(format.encode(1.0), 0x3F80);
+        assert_eq!(format.encode(-2.0), 0xC000);
+        assert_eq!(format.decode(0x3F80), 1.0);
+        assert_eq!(format.decode(format.encode(0.375)), 0.375);
+        /* 0.1 viene arrotondato a 7 bit di mantissa */
+        assert_eq!(format.quantize(0.1), 0.10009765625);
+    }
+
+    #[test]
+    fn bfloat16_rounds_ties_to_even() {
+        let format = NumericFormat::BFloat16;
+        /* a metà tra 0x3F80 (pari) e 0x3F81: resta pari */
+        assert_eq!(format.encode(f32::from_bits(0x3F80_8000) as f64), 0x3F80);
+        /* a metà tra 0x3F81 (dispari) e 0x3F82: arrotondato al pari */
+        assert_eq!(format.encode(f32::from_bits(0x3F81_8000) as f64), 0x3F82);
+        /* appena sopra la metà: arrotondato per eccesso */
+        assert_eq!(format.encode(f32::from_bits(0x3F80_8001) as f64), 0x3F81);
+        /* appena sotto la metà: arrotondato per difetto */
+        assert_eq!(format.encode(f32::from_bits(0x3F80_7FFF) as f64), 0x3F80);
+    }
+
+    #[test]
+    fn bfloat16_nan_and_overflow() {
+        let format = NumericFormat::BFloat16;
+        /* un NaN resta NaN, anche se i bit non nulli della mantissa sono solo tra quelli troncati */
+        assert!(format.decode(format.encode(f64::NAN)).is_nan());
+        assert!(format.decode(format.encode(f32::from_bits(0x7F80_0001) as f64)).is_nan());
+        /* oltre il massimo rappresentabile l'arrotondamento porta all'infinito */
+        assert_eq!(format.encode(f32::MAX as f64), 0x7F80);
+        assert_eq!(format.quantize(1e300), f64::INFINITY);
+        assert_eq!(format.quantize(-1e300), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn fixed_round_trip() {
+        let format = NumericFormat::fixed(3, 12, Overflow::Saturate);
+        assert_eq!(format.width(), 16);
+        assert_eq!(format.encode(1.5), 0x1800);
+        assert_eq!(format.encode(-1.0), 0xF000);
+        assert_eq!(format.decode(0xF000), -1.0);
+        assert_eq!(format.decode(format.encode(-3.25)), -3.25);
+        /* arrotondamento al multiplo di 2^-12 più vicino */
+        assert_eq!(format.quantize(0.1), 410.0 / 4096.0);
+        /* i valori non rappresentabili vengono memorizzati come 0 */
+        assert_eq!(format.encode(f64::NAN), 0);
+
+        let format = NumericFormat::fixed(31, 32, Overflow::Saturate);
+        assert_eq!(format.width(), 64);
+        assert_eq!(format.quantize(-1.25), -1.25);
+    }
+
+    #[test]
+    fn fixed_overflow() {
+        let saturate = NumericFormat::fixed(3, 12, Overflow::Saturate);
+        assert_eq!(saturate.encode(100.0), 0x7FFF);
+        assert_eq!(saturate.quantize(100.0), 8.0 - 1.0 / 4096.0);
+        assert_eq!(saturate.encode(-100.0), 0x8000);
+        assert_eq!(saturate.quantize(-100.0), -8.0);
+        assert_eq!(saturate.quantize(f64::INFINITY), 8.0 - 1.0 / 4096.0);
+
+        let wrap = NumericFormat::fixed(3, 12, Overflow::Wrap);
+        /* 9.0 = 0x9000 su 16 bit, interpretato in complemento a 2 */
+        assert_eq!(wrap.encode(9.0), 0x9000);
+        assert_eq!(wrap.quantize(9.0), -7.0);
+    }
+
+    #[test]
+    fn fixed_validity() {
+        let format = NumericFormat::Fixed { int_bits: 200, frac_bits: 100, overflow: Overflow::Saturate };
+        assert!(!format.is_valid());
+        assert_eq!(format.width(), u8::MAX);
+        assert!(NumericFormat::Fixed { int_bits: 31, frac_bits: 32, overflow: Overflow::Wrap }.is_valid());
+        assert!(!NumericFormat::Fixed { int_bits: 32, frac_bits: 32, overflow: Overflow::Wrap }.is_valid());
+    }
+
+    #[test]
+    fn bit_fields() {
+        let format = NumericFormat::BFloat16;
+        assert_eq!(format.field_of(15), BitField::Sign);
+        assert_eq!(format.field_of(7), BitField::Exponent);
+        assert_eq!(format.field_of(6), BitField::Mantissa);
+        assert_eq!(format.positions_in(&[BitField::Exponent]), (7..15).collect::<Vec<u8>>());
+
+        let format = NumericFormat::fixed(3, 12, Overflow::Saturate);
+        assert_eq!(format.field_of(15), BitField::Sign);
+        assert_eq!(format.field_of(12), BitField::Integer);
+        assert_eq!(format.field_of(11), BitField::Fraction);
+    }
+}
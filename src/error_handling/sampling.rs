@@ -0,0 +1,158 @@
+use rand::Rng;
+use crate::error_handling::fault::Fault;
+
+/// Ritorna il quantile della distribuzione normale standard per la probabilità `p` in (0, 1),
+/// approssimato con l'algoritmo di Acklam (errore relativo inferiore a 1.2e-9)
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02,
+                         1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02,
+                         6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00,
+                         -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00, 3.75440816110066e+00];
+    /* la distribuzione è simmetrica: si calcola solo la metà superiore */
+    if p < 0.5 {
+        return -normal_quantile(1.0 - p);
+    }
+    if p <= 1.0 - 0.02425 {
+        let r = p - 0.5;
+        let s = r * r;
+        (((((A[0] * s + A[1]) * s + A[2]) * s + A[3]) * s + A[4]) * s + A[5]) * r
+            / (((((B[0] * s + B[1]) * s + B[2]) * s + B[3]) * s + B[4]) * s + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    }
+}
+
+/// Ritorna il valore critico della distribuzione normale per il livello di confidenza `confidence` (e.g. `0.95` -> 1.96)
+pub fn z_score(confidence: f64) -> f64 {
+    normal_quantile(1.0 - (1.0 - confidence) / 2.0)
+}
+
+/// Ritorna il numero di errori da iniettare, scelti casualmente da una popolazione di `population` errori,
+/// per stimare la percentuale di inferenze affette con margine di errore `margin` (e.g. `0.01` per ±1%)
+/// e livello di confidenza `confidence` (e.g. `0.95`), secondo la formula per popolazioni finite
+/// `n = N / (1 + e^2 (N - 1) / (t^2 p (1 - p)))`, con `p = 0.5` (caso peggiore)
+pub fn sample_size(population: usize, margin: f64, confidence: f64) -> usize {
+    assert!(margin > 0.0 && margin < 1.0, "Error: the error margin must be in (0, 1), found {}", margin);
+    assert!(confidence > 0.0 && confidence < 1.0, "Error: the confidence level must be in (0, 1), found {}", confidence);
+    if population == 0 {
+        return 0;
+    }
+    let t = z_score(confidence);
+    let n = population as f64 / (1.0 + margin * margin * (population as f64 - 1.0) / (t * t * 0.25));
+    (n.ceil() as usize).min(population)
+}
+
+/// Ritorna l'intervallo di confidenza, al livello `confidence`, della proporzione di inferenze affette
+/// stimata da `affected` inferenze affette su `sampled` errori scelti da una popolazione di `population` errori
+/// (con correzione per popolazione finita); gli estremi sono proporzioni in [0, 1]
+pub fn confidence_interval(affected: usize, sampled: usize, population: usize, confidence: f64) -> (f64, f64) {
+    if sampled == 0 {
+        return (0.0, 1.0);
+    }
+    let p = affected as f64 / sampled as f64;
+    /* un campione che copre tutta la popolazione non ha incertezza */
+    let correction = match population > 1 {
+        true => (population as f64 - sampled as f64) / (population as f64 - 1.0),
+        false => 0.0,
+    };
+    let half_width = z_score(confidence) * (p * (1.0 - p) / sampled as f64 * correction).sqrt();
+    ((p - half_width).max(0.0), (p + half_width).min(1.0))
+}
+
+/// Sceglie casualmente con il generatore `rng`, senza ripetizioni, `n` errori della popolazione `population`,
+/// mantenendone l'ordine
+pub fn sample_faults<R: Rng>(population: &[Fault], n: usize, rng: &mut R) -> Vec<Fault> {
+    let mut indices = rand::seq::index::sample(rng, population.len(), n.min(population.len())).into_vec();
+    indices.sort_unstable();
+    indices.into_iter().map(|index| population[index]).collect()
+}
+
+/// Ritorna il seme dell'inferenza `run` di una campagna con seme `seed`: ogni inferenza ha un proprio generatore
+/// di numeri casuali, per cui può essere riprodotta singolarmente a partire dal seme della campagna e dal suo indice.
+/// I semi vengono mescolati con SplitMix64, in modo che inferenze consecutive abbiano sequenze scorrelate
+pub fn run_seed(seed: u64, run: usize) -> u64 {
+    let mut z = seed.wrapping_add((run as u64).wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn z_score_of_common_confidence_levels() {
+        assert!((z_score(0.95) - 1.959964).abs() < 1e-6);
+        assert!((z_score(0.99) - 2.575829).abs() < 1e-6);
+        assert!((z_score(0.90) - 1.644854).abs() < 1e-6);
+        /* code della distribuzione (ramo di Acklam per p > 0.97575) */
+        assert!((z_score(0.999) - 3.290527).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sample_size_of_an_infinite_population() {
+        /* n = t^2 p (1-p) / e^2 = 1.96^2 * 0.25 / 0.01^2 */
+        assert_eq!(sample_size(usize::MAX, 0.01, 0.95), 9604);
+        assert_eq!(sample_size(usize::MAX, 0.05, 0.95), 385);
+    }
+
+    #[test]
+    fn sample_size_with_finite_population_correction() {
+        assert_eq!(sample_size(0, 0.01, 0.95), 0);
+        assert_eq!(sample_size(1, 0.01, 0.95), 1);
+        assert_eq!(sample_size(100, 0.01, 0.95), 99);
+        assert_eq!(sample_size(1000, 0.01, 0.95), 906);
+        assert_eq!(sample_size(100_000, 0.01, 0.95), 8763);
+        /* il campione non supera mai la popolazione */
+        assert!(sample_size(10, 0.5, 0.5) <= 10);
+    }
+
+    #[test]
+    fn confidence_interval_is_clamped() {
+        /* nessuna inferenza affetta: l'estremo inferiore non scende sotto 0 */
+        assert_eq!(confidence_interval(0, 100, 10_000, 0.95), (0.0, 0.0));
+        let (low, high) = confidence_interval(1, 10, 1000, 0.95);
+        assert_eq!(low, 0.0);
+        assert!(high > 0.1 && high < 1.0);
+        /* tutte le inferenze affette: l'estremo superiore non supera 1 */
+        let (low, high) = confidence_interval(9, 10, 1000, 0.95);
+        assert!(low > 0.5 && low < 0.9);
+        assert_eq!(high, 1.0);
+        /* nessun campione: intervallo massimo */
+        assert_eq!(confidence_interval(0, 0, 1000, 0.95), (0.0, 1.0));
+    }
+
+    #[test]
+    fn confidence_interval_of_the_whole_population() {
+        /* un campione che copre tutta la popolazione non ha incertezza */
+        assert_eq!(confidence_interval(30, 100, 100, 0.95), (0.3, 0.3));
+        let (low, high) = confidence_interval(30, 100, 1_000_000, 0.95);
+        assert!((high - low - 2.0 * 1.959964 * (0.3f64 * 0.7 / 100.0).sqrt()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn sampled_faults_are_distinct_and_ordered() {
+        let population: Vec<Fault> = (0..50).map(|neuron| Fault { component: 1, error_type: 2, layer: 0, neuron,
+            weight: None, mask: 1, instant: None, input_errors: (3, 3) }).collect();
+        let mut rng = StdRng::seed_from_u64(7);
+        let sample = sample_faults(&population, 20, &mut rng);
+        assert_eq!(sample.len(), 20);
+        assert!(sample.windows(2).all(|pair| pair[0].neuron < pair[1].neuron));
+        assert_eq!(sample_faults(&population, 80, &mut rng).len(), 50);
+    }
+
+    #[test]
+    fn run_seeds_are_reproducible_and_distinct() {
+        assert_eq!(run_seed(42, 3), run_seed(42, 3));
+        assert_ne!(run_seed(42, 3), run_seed(42, 4));
+        assert_ne!(run_seed(42, 0), run_seed(43, 0));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod models;
+pub mod snn;
+pub mod error_handling;
+pub mod print_report;
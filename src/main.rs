@@ -1,100 +1,241 @@
-use crate::models::lifneuron::LIFNeuron;
-use crate::snn::snn_builder::SnnBuilder;
-use crate::print_report::info_table::InfoTable;
-use crate::print_report::menu_handler;
+use group12::models::lifneuron::LIFNeuron;
+use group12::models::alifneuron::ALIFNeuron;
+use group12::models::izhikevichneuron::IzhikevichNeuron;
+use group12::snn::neuron::Neuron;
+use group12::snn::snn_builder::{BuildError, SnnBuilder};
+use group12::snn::network_file::{InputFile, NetworkModel, NeuronModel};
+use group12::print_report::info_table::InfoTable;
+use group12::print_report::menu_handler::{self, CampaignMode};
+use group12::print_report::cli::{self, Cli, Command, Options};
+use group12::error_handling::sampling;
+use group12::error_handling::fault::{FaultList, FaultRun, InjectionConfig};
+use std::fmt::Debug;
 use std::fs::File;
-mod models;
-mod snn;
-mod error_handling;
-mod print_report;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 
 
 fn main(){
-    let mut components =Vec::<i32>::new();
-    let mut error_index = -1;
-    let mut n_faults = 0;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    /* senza sottocomandi le opzioni vengono chieste dal menu interattivo */
+    let (command, options) = match cli::parse_args(&args) {
+        Ok(Cli::Interactive { network }) => {
+            let mut options = Options { network, ..Options::default() };
+            let mut error_types = vec![];
+            menu_handler::print_menu(&mut options.components, &mut options.error_index, &mut error_types, &mut options.n_faults,
+                                     &mut options.mode, &mut options.seed, &mut options.replay);
+            /* la scelta tra più tipi di errore fa parte della configurazione dell'iniezione */
+            if !error_types.is_empty() {
+                options.config = Some(InjectionConfig { error_types, ..InjectionConfig::default() });
+            }
+            (None, options)
+        },
+        Ok(Cli::Command(command, options)) => (Some(command), *options),
+        Ok(Cli::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        },
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        },
+    };
+    /* la rete viene letta dal file indicato, con il modello di neurone specificato nel file;
+        altrimenti si usa quella predefinita */
+    match options.network.clone() {
+        Some(path) => match NeuronModel::from_file(&path)
+            .unwrap_or_else(|e| fail(format!("unable to load network from {}: {}", path, e))) {
+            NeuronModel::Lif => run(command, options, load_network::<LIFNeuron>(&path)),
+            NeuronModel::Alif => run(command, options, load_network::<ALIFNeuron>(&path)),
+            NeuronModel::Izhikevich => run(command, options, load_network::<IzhikevichNeuron>(&path)),
+        },
+        None => run(command, options, default_network().unwrap_or_else(|e| fail(format!("invalid default network: {}", e)))),
+    }
+}
+/// Legge dal file `path` una rete di neuroni di tipo `N`
+fn load_network<N: Neuron+Clone+Debug+Serialize+DeserializeOwned+NetworkModel+'static>(path: &str) -> SnnBuilder<N> {
+    SnnBuilder::from_file(path).unwrap_or_else(|e| fail(format!("unable to load network from {}: {}", path, e)))
+}
+/// Esegue l'inferenza senza errori e la campagna di iniezione descritte da `command` e `options`
+/// sulla rete costruita da `builder`, qualunque sia il modello dei suoi neuroni
+fn run<N: Neuron+Clone+Debug+'static>(command: Option<Command>, mut options: Options, mut builder: SnnBuilder<N>) {
     let mut table = InfoTable::new();
-    let mut file = File::create("report.txt").expect("Unable to create file");
+    let mut file = File::create(&options.output)
+        .unwrap_or_else(|e| fail(format!("unable to create file {}: {}", options.output, e)));
+    /* una lista di errori viene ripetuta con la configurazione dell'iniezione con cui è stata salvata,
+        a meno che le opzioni non ne specifichino una diversa */
+    let fault_list = match &options.mode {
+        CampaignMode::FaultList { path } => Some(FaultList::from_file(path)
+            .unwrap_or_else(|e| fail(format!("unable to load fault list from {}: {}", path, e)))),
+        _ => None,
+    };
+    /* il seme viene riportato nel report e salvato con la lista di errori, in modo che la campagna (o una sua inferenza)
+        possa essere riprodotta: ripetendo una lista si usa il suo seme, a meno che non ne venga specificato uno diverso */
+    let seed = options.seed.or(fault_list.as_ref().and_then(|list| list.seed)).unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    if let Some(config) = options.config.as_ref().or(fault_list.as_ref().map(|list| &list.config)) {
+        builder.set_injection_config(config)
+            .unwrap_or_else(|e| fail(format!("invalid fault injection configuration: {}", e)));
+    }
 
-    menu_handler::print_menu(&mut components,&mut  error_index, &mut n_faults);
-    menu_handler::print_configuration(&components, error_index, n_faults);
+    let input = match &options.input {
+        Some(path) => InputFile::from_file(path)
+            .unwrap_or_else(|e| fail(format!("unable to load input from {}: {}", path, e))).spikes,
+        None => vec![vec![0,1,1], vec![0,0,1], vec![1,1,1], vec![1,0,0], vec![0,0,1], vec![0,1,0]],
+    };
+    builder.set_input_length(input.len() as u64);
+    /* SNN WITHOUT ANY ERROR */
+    let mut snn_0_error = builder.clone().build_dyn(&Vec::new(), -1, &mut table)
+        .unwrap_or_else(|e| fail(format!("unable to build network: {}", e)));
+    let snn_result_0_error= snn_0_error.process(&input).unwrap_or_else(|e| fail(format!("invalid input: {}", e)));
+    let golden = command == Some(Command::Golden)
+        || (options.components.is_empty() && !matches!(options.mode, CampaignMode::FaultList { .. }));
+    if golden {
+        table.print_no_error(&mut file, &snn_result_0_error, &input)
+            .unwrap_or_else(|e| fail(format!("unable to write on file {}: {}", options.output, e)));
+        return;
+    }
+    /* nelle campagne esaustiva e statistica ogni inferenza inietta uno degli errori enumerati,
+        con una lista di errori ogni inferenza inietta l'insieme di errori salvato per quell'inferenza */
+    let population = match options.mode {
+        CampaignMode::Exhaustive | CampaignMode::Statistical { .. } => builder.exhaustive_faults(&options.components, options.error_index, input.len() as u64)
+            .unwrap_or_else(|e| fail(format!("unable to enumerate the faults: {}", e))),
+        _ => vec![],
+    };
+    let fault_sets: Vec<FaultRun> = match &options.mode {
+        CampaignMode::Random => vec![],
+        CampaignMode::Exhaustive => population.iter().enumerate().map(|(run, &fault)| FaultRun { run, faults: vec![fault] }).collect(),
+        CampaignMode::Statistical { margin, confidence } => {
+            table.set_sampling(population.len(), *confidence);
+            sampling::sample_faults(&population, sampling::sample_size(population.len(), *margin, *confidence), &mut rng)
+                .into_iter().enumerate().map(|(run, fault)| FaultRun { run, faults: vec![fault] }).collect()
+        },
+        CampaignMode::FaultList { .. } => fault_list.map(|list| list.runs).unwrap_or_default(),
+    };
+    /* tipi di errore riportati nel report: quelli tra cui viene scelto ciascun errore, se più d'uno, o quello selezionato */
+    let mut error_types = match (builder.error_types(), options.error_index) {
+        ([], -1) => vec![],
+        ([], error_index) => vec![error_index],
+        (error_types, _) => error_types.to_vec(),
+    };
+    if let CampaignMode::FaultList { .. } = options.mode {
+        /* componenti e tipi di errore riportati nel report sono quelli della lista */
+        options.components = fault_sets.iter().flat_map(|run| &run.faults).map(|fault| fault.component).collect();
+        options.components.sort();
+        options.components.dedup();
+        error_types = fault_sets.iter().flat_map(|run| &run.faults).map(|fault| fault.error_type).collect();
+        error_types.sort();
+        error_types.dedup();
+    }
+    if options.mode != CampaignMode::Random {
+        options.n_faults = fault_sets.len() as i32;
+    }
+    let Options { components, error_index, n_faults, mode, replay, .. } = &options;
+    menu_handler::print_configuration(components, &error_types, *n_faults, mode, seed, *replay);
+    /* ogni inferenza ha un proprio seme, ricavato da quello della campagna: può quindi essere riprodotta da sola;
+        le inferenze di una lista di errori vengono identificate dal loro indice nella campagna originale */
+    let campaign_runs: Vec<usize> = match mode {
+        CampaignMode::Random => (0..*n_faults as usize).collect(),
+        _ => fault_sets.iter().map(|run| run.run).collect(),
+    };
+    let runs = match *replay {
+        Some(run) if campaign_runs.contains(&run) => vec![run],
+        Some(run) => fail(format!("run {} does not exist in the campaign ({} runs)", run, campaign_runs.len())),
+        None => campaign_runs,
+    };
+    /* SNN WITH ERRORS */
+    for run in runs {
+        table.set_run(run);
+        let mut run_builder = builder.clone();
+        run_builder.set_seed(sampling::run_seed(seed, run));
+        let mut snn = match mode {
+            CampaignMode::Random => run_builder.build_dyn(components, *error_index, &mut table),
+            _ => {
+                let faults = fault_sets.iter().find(|fault_run| fault_run.run == run).map(|fault_run| &fault_run.faults[..]).unwrap_or(&[]);
+                run_builder.build_dyn_with_faults(faults, &mut table)
+            },
+        }.unwrap_or_else(|e| fail(format!("unable to inject the fault set: {}", e)));
+        let snn_result= snn.process(&input).unwrap_or_else(|e| fail(format!("invalid input: {}", e)));
+        let acc = calculate_accuracy(&snn_result_0_error, &snn_result);
+        table.add_transients(snn.transient_windows());
+        table.add_fires(snn.fires());
+        table.add_values(snn.values());
+        table.add_output((1.0-acc)*100.0);
+    }
 
+    menu_handler::write_configuration_to_file(&mut file, components, &error_types, *n_faults, mode, seed, *replay)
+        .and_then(|_| table.print_table(&mut file))
+        .unwrap_or_else(|e| fail(format!("unable to write on file {}: {}", options.output, e)));
+    /* la lista degli errori iniettati permette di ripetere la campagna (e.g. su una rete modificata) */
+    table.fault_list(builder.injection_config(), seed).to_file(&options.fault_list_output)
+        .unwrap_or_else(|e| fail(format!("unable to write the fault list {}: {}", options.fault_list_output, e)));
+}
+/// Rete predefinita, utilizzata quando non viene specificato un file di definizione della rete
+/// (la stessa rete è salvata in `networks/default.toml`)
+fn default_network() -> Result<SnnBuilder<LIFNeuron>, BuildError> {
     let mut binding = SnnBuilder::new();
-    let builder = binding.add_layer().add_weight([
+    binding.add_layer().add_weight([
         [0.1, 0.2, 0.5],
         [0.3, 0.4, 0.2],
         [0.5, 0.6, 0.1]
-    ]).add_neurons([
+    ])?.add_neurons([
                         LIFNeuron::new(0.03, 0.05, 0.1, 1.0, 1.0),
                         LIFNeuron::new(0.05, 0.05, 0.1, 1.0, 1.0),
                         LIFNeuron::new(0.09, 0.05, 0.1, 1.0, 1.0),
-    ]).add_intra_weights([
+    ])?.add_intra_weights([
         [0.0, -0.25, -0.3],
         [-0.10, 0.0, -0.3],
         [-0.1, -0.3,  0.0]
-    ])
+    ])?
     .add_layer()
         .add_weight([
             [0.1, 0.2, 0.3],
             [0.4, 0.5, 0.6]
-        ]).add_neurons([
+        ])?.add_neurons([
         LIFNeuron::new(0.07, 0.04, 0.4, 1.0, 1.0),
         LIFNeuron::new(0.3, 0.01, 0.4, 1.0, 1.0),
-    ]).add_intra_weights([
+    ])?.add_intra_weights([
         [0.0, -0.25],
         [-0.10, 0.0]
-    ]).add_layer().add_weight([
+    ])?.add_layer().add_weight([
         [0.1, 0.2],
         [0.3, 0.4],
         [0.5, 0.6]
-    ]).add_neurons([
+    ])?.add_neurons([
         LIFNeuron::new(0.03, 0.01, 0.1, 1.0, 1.0),
         LIFNeuron::new(0.05, 0.03, 0.2, 1.0, 1.0),
         LIFNeuron::new(0.09, 0.06, 0.4, 1.0, 1.0),
-    ]).add_intra_weights([
+    ])?.add_intra_weights([
         [0.0, -0.25, -0.3],
         [-0.10, 0.0, -0.3],
         [-0.1, -0.3,  0.0]
-    ]).add_layer()
+    ])?.add_layer()
         .add_weight([
             [0.1, 0.2, 0.3],
             [0.4, 0.5, 0.6]
-        ]).add_neurons([
+        ])?.add_neurons([
         LIFNeuron::new(0.07, 0.01, 0.2, 1.0, 1.0),
         LIFNeuron::new(0.03, 0.08, 0.3, 1.0, 1.0),
-    ]).add_intra_weights([
+    ])?.add_intra_weights([
         [0.0, -0.25],
         [-0.10, 0.0]
-    ]);
-
-    let input = [[0,1,1], [0,0,1], [1,1,1], [1,0,0], [0,0,1], [0,1,0]];
-    /* SNN WITHOUT ANY ERROR */
-    let mut snn_0_error = builder.clone().build::<3,2>(&Vec::new(), -1, &mut table);
-    let snn_result_0_error= snn_0_error.process(&input);
-    /* SNN WITH ERRORS */
-    for _ in 0..n_faults {
-        let mut snn = builder.clone().build::<3,2>(&components, error_index, &mut table);
-        let snn_result= snn.process(&input);
-        let acc = calculate_accuracy(&snn_result_0_error, &snn_result);
-        table.add_output((1.0-acc)*100.0);
-    }
-
-    if components.len() != 0{
-        menu_handler::write_configuration_to_file(&mut file, &components, error_index, n_faults).expect("nable to write on file");
-        table.print_table(&mut file).expect("Unable To write Statics");
-    }else{
-        table.print_no_error(&mut file, &snn_result_0_error, &input).expect("Unable to write on file");
-    }
-
-
+    ])?;
+    Ok(binding)
+}
+/// Stampa l'errore su stderr e termina il programma: gli errori dell'utente (file, opzioni o reti non valide)
+/// non devono causare un panic
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
 }
-fn calculate_accuracy<const SNN_OUTPUT_DIM: usize, const SPIKES_DURATION: usize>(v1: &[[u8; SNN_OUTPUT_DIM]; SPIKES_DURATION], v2: &[[u8; SNN_OUTPUT_DIM]; SPIKES_DURATION]) -> f64 {
+fn calculate_accuracy(v1: &[Vec<u8>], v2: &[Vec<u8>]) -> f64 {
     let total_elements = v1.iter().map(|row| row.len()).sum::<usize>();
     let matching_elements = v1.iter().zip(v2.iter())
         .map(|(row1, row2)| row1.iter().zip(row2.iter()).filter(|&(elem1, elem2)| elem1 == elem2).count())
         .sum::<usize>();
 
-    let accuracy = matching_elements as f64 / total_elements as f64;
-    accuracy
+    matching_elements as f64 / total_elements as f64
 }
 
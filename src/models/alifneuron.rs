@@ -0,0 +1,281 @@
+use crate::snn::neuron::Neuron;
+use crate::error_handling::components::{Adder, Comparator, ExpUnit, Multiplier};
+use crate::error_handling::error_handling::ErrorBit;
+use crate::error_handling::numeric_format::NumericFormat;
+use serde::{Deserialize, Serialize};
+
+/// Struttura che rappresenta un neurone di tipo *Adaptive Leaky Integrate and Fire* (**ALIF**):
+/// un `LIFNeuron` la cui soglia effettiva `v_th + theta` cresce di `beta` ad ogni impulso
+/// e la cui variabile di adattamento `theta` decade verso 0 con costante di tempo `tau_adapt`.
+/// Nei file di definizione della rete vengono salvati solo i parametri costanti (vedi `ALIFParams`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "ALIFParams", into = "ALIFParams")]
+pub struct ALIFNeuron{
+    /* campi costanti */
+    /// potenziale di soglia di base
+    v_th: f64,
+    /// potenziale di riposo
+    v_rest: f64,
+    ///potenziale di reset
+    v_reset: f64,
+    /// costante di tempo del potenziale di membrana *tau=C·R*
+    tau: f64,
+    /// intervallo di tempo tra due istanti successivi
+    d_t: f64,
+    /// costante di tempo della variabile di adattamento
+    tau_adapt: f64,
+    /// incremento della variabile di adattamento ad ogni impulso
+    beta: f64,
+    /*campi mutabili*/
+    /// potenziale di membrana
+    v_mem: f64,
+    /// variabile di adattamento, i.e. incremento corrente della soglia
+    theta: f64,
+    /// ultimo istante di tempo in cui si è ricevuto un impulso
+    t_s: u64,
+    /// *eventuale* errore su un bit del potenziale di membrana
+    membrane_error: Option<ErrorBit>,
+    /// *eventuale* errore su un bit della variabile di adattamento
+    adaptation_error: Option<ErrorBit>
+}
+
+/// Parametri costanti di un `ALIFNeuron`, come appaiono nei file di definizione della rete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ALIFParams{
+    pub v_th: f64,
+    pub v_rest: f64,
+    pub v_reset: f64,
+    pub tau: f64,
+    pub d_t: f64,
+    pub tau_adapt: f64,
+    pub beta: f64,
+}
+
+impl From<ALIFParams> for ALIFNeuron {
+    fn from(p: ALIFParams) -> Self {
+        ALIFNeuron::new(p.v_th, p.v_rest, p.v_reset, p.tau, p.d_t, p.tau_adapt, p.beta)
+    }
+}
+
+impl From<ALIFNeuron> for ALIFParams {
+    fn from(n: ALIFNeuron) -> Self {
+        Self {
+            v_th: n.v_th,
+            v_rest: n.v_rest,
+            v_reset: n.v_reset,
+            tau: n.tau,
+            d_t: n.d_t,
+            tau_adapt: n.tau_adapt,
+            beta: n.beta,
+        }
+    }
+}
+
+impl ALIFNeuron {
+/// Ritorna un nuovo neurone di tipo ALIF
+/// # Argomenti
+/// * `v_th` - potenziale di soglia di base
+/// * `v_rest` - potenziale di riposo
+/// * `v_reset` - potenziale di reset
+/// * `tau` - constante di tempo del potenziale di membrana
+/// * `d_t` - intervallo di tempo tra due istanti
+/// * `tau_adapt` - costante di tempo della variabile di adattamento
+/// * `beta` - incremento della soglia ad ogni impulso
+/// # Valori predefiniti
+/// * `v_mem` - potenziale di membrana settato al potenziale di riposo
+/// * `theta` - variabile di adattamento settata a 0
+/// * `t_s` - istante di tempo iniziale settato a 0
+/// * `membrane_error`, `adaptation_error` - nessun errore (Option::None)
+    pub fn new(v_th: f64, v_rest: f64, v_reset: f64, tau: f64, d_t: f64, tau_adapt: f64, beta: f64) -> Self {
+        Self {
+            v_th,
+            v_rest,
+            v_reset,
+            tau,
+            d_t,
+            tau_adapt,
+            beta,
+            v_mem: v_rest,
+            theta: 0.0,
+            t_s: 0u64,
+            membrane_error: None,
+            adaptation_error: None
+        }
+    }
+    /*** getters ***/
+    pub fn v_rest(&self) -> f64 {
+        self.v_rest
+    }
+    pub fn v_reset(&self) -> f64 {
+        self.v_reset
+    }
+    pub fn tau(&self) -> f64 {
+        self.tau
+    }
+    pub fn d_t(&self) -> f64 {
+        self.d_t
+    }
+    pub fn tau_adapt(&self) -> f64 {
+        self.tau_adapt
+    }
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+/// Funzione per controllare la presenza di un errore stuck-at-X su un bit del
+/// potenziale di membrana ed eventualmente forzare tale bit ad X
+    fn check_error(&mut self){
+        if let Some(error) = self.membrane_error.as_ref(){
+            self.v_mem = error.force_value(self.v_mem);
+        }
+    }
+/// Funzione per controllare la presenza di un errore stuck-at-X su un bit della
+/// variabile di adattamento ed eventualmente forzare tale bit ad X
+    fn check_adaptation_error(&mut self){
+        if let Some(error) = self.adaptation_error.as_ref(){
+            self.theta = error.force_value(self.theta);
+        }
+    }
+}
+
+impl Neuron for ALIFNeuron{
+    /* come per il LIFNeuron, gli errori stuck-at-X vengono forzati prima di ciascun utilizzo
+        del potenziale di membrana e della variabile di adattamento *(i.e. formula e confronto)* */
+    fn update_v_mem(&mut self, t: u64, intra_weight: f64, extra_weight: f64, adder: Adder, mult: Multiplier, exp_unit: ExpUnit, cmp: Comparator) -> u8 {
+        /* intra_weight+extra_weight */
+        let weight_sum = adder.add(intra_weight,extra_weight);
+        /* ((t-self.t_s)as f64)*self.d_t; come per il LIFNeuron, in virgola fissa la differenza tra gli istanti
+            non passa dal sommatore del datapath */
+        let elapsed = match adder.format() {
+            NumericFormat::Fixed { .. } => t.saturating_sub(self.t_s) as f64,
+            _ => adder.sub(t as f64, self.t_s as f64),
+        };
+        let elapsed = mult.mul(elapsed,self.d_t);
+        /* -elapsed/self.tau */
+        let exponent = -mult.div(elapsed,self.tau);
+        /* -elapsed/self.tau_adapt */
+        let adapt_exponent = -mult.div(elapsed,self.tau_adapt);
+
+        self.check_error();
+        /* self.v_rest + (self.v_mem-self.v_rest)*exponent.exp() + weight_sum */
+        self.v_mem = adder.add(adder.add(self.v_rest ,mult.mul(adder.sub(self.v_mem,self.v_rest),exp_unit.exp(exponent))),weight_sum);
+        self.check_adaptation_error();
+        /* self.theta*adapt_exponent.exp() */
+        self.theta = mult.mul(self.theta, exp_unit.exp(adapt_exponent));
+
+        self.t_s = t;
+        /* controllo sugli errori prima del confronto con la soglia effettiva */
+        self.check_error();
+        self.check_adaptation_error();
+        /* confronto con la soglia effettiva self.v_th+self.theta e ritorno del segnale */
+        if cmp.gt(self.v_mem, adder.add(self.v_th, self.theta)) {
+            self.v_mem = self.v_reset;
+            /* self.theta+self.beta */
+            self.theta = adder.add(self.theta, self.beta);
+            self.check_adaptation_error();
+            1
+        } else {
+            0
+        }
+    }
+
+    fn init_neuron(&mut self) {
+        self.v_mem = self.v_rest;
+        self.theta = 0.0;
+        self.t_s = 0u64;
+        self.membrane_error = None;
+        self.adaptation_error = None;
+    }
+    fn set_membrane_error(&mut self, error_type: u8, mask: u64, format: NumericFormat) {
+        self.membrane_error = Some(ErrorBit::new(error_type, mask).with_format(format));
+    }
+    fn get_th(&self) -> f64 {
+        self.v_th
+    }
+    fn set_th(&mut self, new_th: f64) {
+        self.v_th = new_th;
+    }
+    fn get_mem(&self) -> f64 {
+        self.v_mem
+    }
+    fn set_mem(&mut self, new_mem: f64) {
+        self.v_mem = new_mem;
+    }
+    fn set_aux_error(&mut self, error_type: u8, mask: u64, format: NumericFormat) {
+        self.adaptation_error = Some(ErrorBit::new(error_type, mask).with_format(format));
+    }
+    fn get_aux(&self) -> f64 {
+        self.theta
+    }
+    fn set_aux(&mut self, new_aux: f64) {
+        self.theta = new_aux;
+    }
+    fn has_aux(&self) -> bool {
+        true
+    }
+    fn quantize(&mut self, format: NumericFormat) {
+        self.v_th = format.quantize(self.v_th);
+        self.v_rest = format.quantize(self.v_rest);
+        self.v_reset = format.quantize(self.v_reset);
+        self.beta = format.quantize(self.beta);
+        self.v_mem = format.quantize(self.v_mem);
+        self.theta = format.quantize(self.theta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_handling::components::ArithmeticUnits;
+
+    /// Neurone con soglia di base 1, riposo e reset a 0, `tau_adapt` = 10 e `beta` = 0.5
+    fn neuron() -> ALIFNeuron {
+        ALIFNeuron::new(1.0, 0.0, 0.0, 1.0, 1.0, 10.0, 0.5)
+    }
+    fn step(neuron: &mut ALIFNeuron, t: u64, input: f64) -> u8 {
+        let units = ArithmeticUnits::default();
+        neuron.update_v_mem(t, 0.0, input, units.adder, units.mult, units.exp_unit, units.cmp)
+    }
+
+    #[test]
+    fn threshold_grows_by_beta_after_a_spike() {
+        let mut n = neuron();
+        assert_eq!(step(&mut n, 1, 2.0), 1);
+        assert_eq!((n.get_mem(), n.theta()), (0.0, 0.5));
+        /* 1.2 supera la soglia di base ma non quella effettiva v_th + theta */
+        assert_eq!(step(&mut n, 2, 1.2), 0);
+        let mut lif_like = ALIFNeuron::new(1.0, 0.0, 0.0, 1.0, 1.0, 10.0, 0.0);
+        assert_eq!(step(&mut lif_like, 1, 2.0), 1);
+        assert_eq!(step(&mut lif_like, 2, 1.2), 1);
+        /* init_neuron azzera la variabile di adattamento */
+        n.init_neuron();
+        assert_eq!(n.theta(), 0.0);
+    }
+
+    #[test]
+    fn theta_decays_with_tau_adapt() {
+        let mut n = neuron();
+        step(&mut n, 1, 2.0);
+        /* dopo 10 istanti (= tau_adapt) theta vale beta·e^-1 */
+        assert_eq!(step(&mut n, 11, 0.0), 0);
+        assert!((n.theta() - 0.5 * (-1.0f64).exp()).abs() < 1e-12);
+        step(&mut n, 31, 0.0);
+        assert!((n.theta() - 0.5 * (-3.0f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn adaptation_fault_changes_when_the_neuron_fires() {
+        let mut correct = neuron();
+        let mut faulty = neuron();
+        /* stuck-at-0 su un bit dell'esponente: theta = 0.5 diventa ~0.5·2^-512 */
+        faulty.set_aux_error(0, 1 << 61, NumericFormat::Float64);
+        assert_eq!(step(&mut correct, 1, 2.0), 1);
+        assert_eq!(step(&mut faulty, 1, 2.0), 1);
+        assert!(faulty.theta() < 1e-100);
+        /* senza adattamento il neurone guasto torna a generare un impulso */
+        assert_eq!(step(&mut correct, 2, 1.2), 0);
+        assert_eq!(step(&mut faulty, 2, 1.2), 1);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::snn::neuron::Neuron;
+use crate::error_handling::components::{Adder, Comparator, ExpUnit, Multiplier};
+use crate::error_handling::error_handling::ErrorBit;
+use crate::error_handling::numeric_format::NumericFormat;
+use serde::{Deserialize, Serialize};
+
+/* coefficienti della dinamica del potenziale di membrana: 0.04·v² + 5·v + 140 */
+const V_SQUARE_COEFF: f64 = 0.04;
+const V_COEFF: f64 = 5.0;
+const V_CONST: f64 = 140.0;
+
+/// Struttura che rappresenta un neurone di tipo *Izhikevich*:
+/// * `v' = 0.04·v² + 5·v + 140 - u + I`
+/// * `u' = a·(b·v - u)`
+/// * se `v >= v_th`: `v = c`, `u = u + d`
+///
+/// Le equazioni sono integrate con il metodo di Eulero con passo `d_t` e tutte le operazioni
+/// aritmetiche vengono eseguite da `Adder` e `Multiplier`.
+/// Nei file di definizione della rete vengono salvati solo i parametri costanti (vedi `IzhikevichParams`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "IzhikevichParams", into = "IzhikevichParams")]
+pub struct IzhikevichNeuron{
+    /* campi costanti */
+    /// scala temporale della variabile di recupero
+    a: f64,
+    /// sensibilità della variabile di recupero al potenziale di membrana
+    b: f64,
+    /// potenziale di reset dopo un impulso
+    c: f64,
+    /// incremento della variabile di recupero dopo un impulso
+    d: f64,
+    /// potenziale di picco, oltre il quale il neurone genera un impulso
+    v_th: f64,
+    /// intervallo di tempo tra due istanti successivi (passo di integrazione)
+    d_t: f64,
+    /// coefficienti della dinamica del potenziale di membrana (`0.04`, `5`, `140`), nel formato del datapath
+    v_coeffs: (f64, f64, f64),
+    /// valore iniziale della variabile di recupero, `b·c` nel formato del datapath
+    u_init: f64,
+    /*campi mutabili*/
+    /// potenziale di membrana
+    v_mem: f64,
+    /// variabile di recupero
+    u: f64,
+    /// ultimo istante di tempo in cui si è ricevuto un impulso
+    t_s: u64,
+    /// *eventuale* errore su un bit del potenziale di membrana
+    membrane_error: Option<ErrorBit>,
+    /// *eventuale* errore su un bit della variabile di recupero
+    recovery_error: Option<ErrorBit>
+}
+
+/// Parametri costanti di un `IzhikevichNeuron`, come appaiono nei file di definizione della rete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IzhikevichParams{
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub v_th: f64,
+    pub d_t: f64,
+}
+
+impl From<IzhikevichParams> for IzhikevichNeuron {
+    fn from(p: IzhikevichParams) -> Self {
+        IzhikevichNeuron::new(p.a, p.b, p.c, p.d, p.v_th, p.d_t)
+    }
+}
+
+impl From<IzhikevichNeuron> for IzhikevichParams {
+    fn from(n: IzhikevichNeuron) -> Self {
+        Self {
+            a: n.a,
+            b: n.b,
+            c: n.c,
+            d: n.d,
+            v_th: n.v_th,
+            d_t: n.d_t,
+        }
+    }
+}
+
+impl IzhikevichNeuron {
+/// Ritorna un nuovo neurone di tipo Izhikevich
+/// # Argomenti
+/// * `a` - scala temporale della variabile di recupero (e.g. `0.02`)
+/// * `b` - sensibilità della variabile di recupero (e.g. `0.2`)
+/// * `c` - potenziale di reset (e.g. `-65.0`)
+/// * `d` - incremento della variabile di recupero dopo un impulso (e.g. `8.0`)
+/// * `v_th` - potenziale di picco (e.g. `30.0`)
+/// * `d_t` - intervallo di tempo tra due istanti
+/// # Valori predefiniti
+/// * `v_mem` - potenziale di membrana settato al potenziale di reset `c`
+/// * `u` - variabile di recupero settata a `b·c`
+/// * `t_s` - istante di tempo iniziale settato a 0
+/// * `membrane_error`, `recovery_error` - nessun errore (Option::None)
+    pub fn new(a: f64, b: f64, c: f64, d: f64, v_th: f64, d_t: f64) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            d,
+            v_th,
+            d_t,
+            v_coeffs: (V_SQUARE_COEFF, V_COEFF, V_CONST),
+            u_init: b * c,
+            v_mem: c,
+            u: b * c,
+            t_s: 0u64,
+            membrane_error: None,
+            recovery_error: None
+        }
+    }
+    /*** getters ***/
+    pub fn a(&self) -> f64 {
+        self.a
+    }
+    pub fn b(&self) -> f64 {
+        self.b
+    }
+    pub fn c(&self) -> f64 {
+        self.c
+    }
+    pub fn d(&self) -> f64 {
+        self.d
+    }
+    pub fn d_t(&self) -> f64 {
+        self.d_t
+    }
+    pub fn u(&self) -> f64 {
+        self.u
+    }
+/// Funzione per controllare la presenza di errori stuck-at-X sui bit del potenziale
+/// di membrana e della variabile di recupero ed eventualmente forzare tali bit ad X
+    fn check_error(&mut self){
+        if let Some(error) = self.membrane_error.as_ref(){
+            self.v_mem = error.force_value(self.v_mem);
+        }
+        if let Some(error) = self.recovery_error.as_ref(){
+            self.u = error.force_value(self.u);
+        }
+    }
+/// Esegue un passo di integrazione di Eulero con corrente d'ingresso `input`
+    fn euler_step(&mut self, input: f64, adder: Adder, mult: Multiplier){
+        self.check_error();
+        /* 0.04·v² + 5·v + 140 - u + I */
+        let (v_square_coeff, v_coeff, v_const) = self.v_coeffs;
+        let v_square = mult.mul(v_square_coeff, mult.mul(self.v_mem, self.v_mem));
+        let dv = adder.add(adder.add(adder.add(v_square, mult.mul(v_coeff, self.v_mem)), v_const), adder.sub(input, self.u));
+        /* a·(b·v - u) */
+        let du = mult.mul(self.a, adder.sub(mult.mul(self.b, self.v_mem), self.u));
+
+        self.v_mem = adder.add(self.v_mem, mult.mul(self.d_t, dv));
+        self.u = adder.add(self.u, mult.mul(self.d_t, du));
+        self.check_error();
+    }
+}
+
+impl Neuron for IzhikevichNeuron{
+    /* il neurone viene aggiornato solo quando il layer riceve un Evento: negli istanti trascorsi
+        dall'ultimo aggiornamento il neurone evolve senza input, mentre l'input viene applicato all'istante corrente */
+    fn update_v_mem(&mut self, t: u64, intra_weight: f64, extra_weight: f64, adder: Adder, mult: Multiplier, _exp_unit: ExpUnit, cmp: Comparator) -> u8 {
+        /* intra_weight+extra_weight */
+        let input = adder.add(intra_weight, extra_weight);
+        let elapsed = t.saturating_sub(self.t_s);
+        for _ in 1..elapsed {
+            self.euler_step(0.0, adder, mult);
+        }
+        self.euler_step(input, adder, mult);
+        self.t_s = t;
+
+        /* confronto con il potenziale di picco e ritorno del segnale */
+        if cmp.ge(self.v_mem, self.v_th) {
+            self.v_mem = self.c;
+            self.u = adder.add(self.u, self.d);
+            self.check_error();
+            1
+        } else {
+            0
+        }
+    }
+
+    fn init_neuron(&mut self) {
+        self.v_mem = self.c;
+        self.u = self.u_init;
+        self.t_s = 0u64;
+        self.membrane_error = None;
+        self.recovery_error = None;
+    }
+    fn set_membrane_error(&mut self, error_type: u8, mask: u64, format: NumericFormat) {
+        self.membrane_error = Some(ErrorBit::new(error_type, mask).with_format(format));
+    }
+    fn get_th(&self) -> f64 {
+        self.v_th
+    }
+    fn set_th(&mut self, new_th: f64) {
+        self.v_th = new_th;
+    }
+    fn get_mem(&self) -> f64 {
+        self.v_mem
+    }
+    fn set_mem(&mut self, new_mem: f64) {
+        self.v_mem = new_mem;
+    }
+    fn set_aux_error(&mut self, error_type: u8, mask: u64, format: NumericFormat) {
+        self.recovery_error = Some(ErrorBit::new(error_type, mask).with_format(format));
+    }
+    fn get_aux(&self) -> f64 {
+        self.u
+    }
+    fn set_aux(&mut self, new_aux: f64) {
+        self.u = new_aux;
+    }
+    fn has_aux(&self) -> bool {
+        true
+    }
+    fn uses_exp_unit(&self) -> bool {
+        false
+    }
+    /* tutte le costanti usate dai blocchi elaborativi vengono memorizzate nel formato del datapath;
+        il valore iniziale di `u` è il prodotto dei parametri già arrotondati */
+    fn quantize(&mut self, format: NumericFormat) {
+        self.a = format.quantize(self.a);
+        self.b = format.quantize(self.b);
+        self.c = format.quantize(self.c);
+        self.d = format.quantize(self.d);
+        self.v_th = format.quantize(self.v_th);
+        self.d_t = format.quantize(self.d_t);
+        let (v_square_coeff, v_coeff, v_const) = self.v_coeffs;
+        self.v_coeffs = (format.quantize(v_square_coeff), format.quantize(v_coeff), format.quantize(v_const));
+        self.u_init = format.quantize(self.b * self.c);
+        self.v_mem = format.quantize(self.v_mem);
+        self.u = format.quantize(self.u);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_handling::components::ArithmeticUnits;
+    use crate::error_handling::numeric_format::Overflow;
+
+    /// Neurone *regular spiking* (a = 0.02, b = 0.2, c = -65, d = 8)
+    fn neuron() -> IzhikevichNeuron {
+        IzhikevichNeuron::new(0.02, 0.2, -65.0, 8.0, 30.0, 1.0)
+    }
+    fn step(neuron: &mut IzhikevichNeuron, t: u64, input: f64) -> u8 {
+        let units = ArithmeticUnits::default();
+        neuron.update_v_mem(t, 0.0, input, units.adder, units.mult, units.exp_unit, units.cmp)
+    }
+
+    #[test]
+    fn spike_resets_membrane_and_increments_recovery() {
+        let mut n = neuron();
+        assert_eq!(n.u(), 0.2 * -65.0);
+        /* senza input il neurone a riposo non genera impulsi */
+        assert_eq!(step(&mut n, 1, 0.0), 0);
+        let mut n = neuron();
+        /* a riposo b·v - u = 0, quindi u non cambia durante il passo: dopo l'impulso vale b·c + d */
+        assert_eq!(step(&mut n, 1, 100.0), 1);
+        assert_eq!(n.get_mem(), -65.0);
+        assert_eq!(n.u(), 0.2 * -65.0 + 8.0);
+        /* init_neuron riporta lo stato iniziale */
+        n.init_neuron();
+        assert_eq!((n.get_mem(), n.u()), (-65.0, 0.2 * -65.0));
+    }
+
+    #[test]
+    fn recovery_fault_changes_u() {
+        let mut correct = neuron();
+        let mut faulty = neuron();
+        /* stuck-at-0 sul bit di segno: u = b·c < 0 diventa positivo */
+        faulty.set_aux_error(0, 1 << 63, NumericFormat::Float64);
+        step(&mut correct, 1, 5.0);
+        step(&mut faulty, 1, 5.0);
+        assert!(correct.u() < 0.0);
+        assert!(faulty.u() > 0.0);
+        /* u entra nella dinamica del potenziale di membrana */
+        assert!(faulty.get_mem() < correct.get_mem());
+        /* l'errore resta dopo il reset di un impulso */
+        assert_eq!(step(&mut faulty, 2, 200.0), 1);
+        assert!(faulty.u() > 0.0);
+    }
+
+    #[test]
+    fn quantize_every_datapath_constant() {
+        let format = NumericFormat::fixed(7, 8, Overflow::Saturate);
+        let mut n = IzhikevichNeuron::new(0.02, 0.2, -65.3, 8.01, 30.0, 0.1);
+        n.quantize(format);
+        let (v_square_coeff, v_coeff, v_const) = n.v_coeffs;
+        for value in [n.a, n.b, n.c, n.d, n.v_th, n.d_t, v_square_coeff, v_coeff, v_const, n.v_mem, n.u] {
+            assert_eq!(format.quantize(value), value);
+        }
+        assert_eq!((n.a, n.b, n.d_t), (5.0 / 256.0, 51.0 / 256.0, 26.0 / 256.0));
+        assert_eq!(v_square_coeff, 10.0 / 256.0);
+        /* il valore iniziale di u resta nel formato anche dopo init_neuron */
+        n.init_neuron();
+        assert_eq!(n.u(), format.quantize(n.b * n.c));
+        assert_eq!(format.quantize(n.u()), n.u());
+    }
+}
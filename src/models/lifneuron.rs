@@ -1,22 +1,38 @@
 use crate::snn::neuron::Neuron;
-use crate::error_handling::components::{Adder, Multiplier};
-/// Struttura che rappresenta un errore stuck-at-X su un determinato bit
-#[derive(Clone, Debug)]
-struct ErrorBit{
-    error_type:u8, //0/1
-    position:u8,
+use crate::error_handling::components::{Adder, Comparator, ExpUnit, Multiplier};
+use crate::error_handling::error_handling::ErrorBit;
+use crate::error_handling::numeric_format::NumericFormat;
+use serde::{Deserialize, Serialize};
+
+/// Meccanismo di reset del potenziale di membrana dopo un impulso
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetMode {
+    /// il potenziale di membrana viene riportato al potenziale di reset `v_reset`
+    #[default]
+    ToValue,
+    /// il potenziale di soglia viene sottratto al potenziale di membrana (tramite l'`Adder`),
+    /// mantenendo la carica residua
+    Subtraction,
 }
-impl ErrorBit {
-/// Ritorna un nuovo **ErrorBit*
-/// # Argomenti
-/// * `error_type` - valore a cui il bit è bloccato *(0/1)*
-/// * `position` - posizione del bit bloccato
-    pub fn new(error_type: u8, position: u8) -> Self {
-        Self { error_type, position }
-    }
+
+/// Schema di integrazione del decadimento del potenziale di membrana tra due aggiornamenti
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationScheme {
+    /// soluzione esatta: `v_rest + (v_mem-v_rest)·exp(-(t-t_s)·d_t/tau)`
+    #[default]
+    Exact,
+    /// metodo di Eulero, un passo per ciascun istante trascorso: `v_mem += d_t/tau·(v_rest-v_mem)`
+    Euler,
+    /// decadimento tramite shift, un passo per ciascun istante trascorso: `v_mem -= v_mem >> k`
+    /// (applicato alla distanza dal potenziale di riposo)
+    ShiftLeak(u8),
 }
-/// Struttura che rappresenta un neurone di tipo *Leaky Integrate and Fire* (**LIF**)
-#[derive(Debug)]
+/// Struttura che rappresenta un neurone di tipo *Leaky Integrate and Fire* (**LIF**).
+/// Nei file di definizione della rete vengono salvati solo i parametri costanti (vedi `LIFParams`)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "LIFParams", into = "LIFParams")]
 pub struct LIFNeuron{
     /* campi costanti */
     /// potenziale di soglia
@@ -29,13 +45,61 @@ pub struct LIFNeuron{
     tau: f64,
     /// intervallo di tempo tra due istanti successivi
     d_t: f64,
+    /// durata del periodo refrattario assoluto (in istanti di tempo)
+    t_ref: u64,
+    /// meccanismo di reset dopo un impulso
+    reset_mode: ResetMode,
+    /// schema di integrazione del potenziale di membrana
+    integration: IntegrationScheme,
     /*campi mutabili*/
     /// potenziale di membrana
     v_mem: f64,
     /// ultimo istante di tempo in cui si è ricevuto un impulso
     t_s: u64,
+    /// contatore del periodo refrattario: numero di istanti in cui l'input verrà ancora ignorato
+    refractory: u64,
     /// *eventuale* errore su un bit del potenziale di membrana
-    membrane_error:Option<ErrorBit>
+    membrane_error:Option<ErrorBit>,
+    /// *eventuale* errore su un bit del contatore del periodo refrattario
+    refractory_error:Option<ErrorBit>
+}
+
+/// Parametri costanti di un `LIFNeuron`, come appaiono nei file di definizione della rete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LIFParams{
+    pub v_th: f64,
+    pub v_rest: f64,
+    pub v_reset: f64,
+    pub tau: f64,
+    pub d_t: f64,
+    #[serde(default)]
+    pub t_ref: u64,
+    #[serde(default)]
+    pub reset_mode: ResetMode,
+    #[serde(default)]
+    pub integration: IntegrationScheme,
+}
+
+impl From<LIFParams> for LIFNeuron {
+    fn from(p: LIFParams) -> Self {
+        LIFNeuron::new(p.v_th, p.v_rest, p.v_reset, p.tau, p.d_t).with_refractory(p.t_ref).with_reset_mode(p.reset_mode)
+            .with_integration(p.integration)
+    }
+}
+
+impl From<LIFNeuron> for LIFParams {
+    fn from(n: LIFNeuron) -> Self {
+        Self {
+            v_th: n.v_th,
+            v_rest: n.v_rest,
+            v_reset: n.v_reset,
+            tau: n.tau,
+            d_t: n.d_t,
+            t_ref: n.t_ref,
+            reset_mode: n.reset_mode,
+            integration: n.integration,
+        }
+    }
 }
 
 impl LIFNeuron {
@@ -47,9 +111,14 @@ impl LIFNeuron {
 /// * `tau` - constante di tempo
 /// * `d_t` - intervallo di tempo tra due istanti
 /// # Valori predefiniti
+/// * `t_ref` - nessun periodo refrattario (0 istanti)
+/// * `reset_mode` - reset al potenziale `v_reset` (`ResetMode::ToValue`)
+/// * `integration` - decadimento esponenziale esatto (`IntegrationScheme::Exact`)
 /// * `v_mem` - potenziale di membrana settato al potenziale di riposo
 /// * `t_s` - istante di tempo iniziale settato a 0
+/// * `refractory` - contatore del periodo refrattario settato a 0
 /// * `membrane_error` - nessun errore sulla memprana (Option::None)
+/// * `refractory_error` - nessun errore sul contatore del periodo refrattario (Option::None)
     pub fn new(v_th: f64, v_rest: f64, v_reset: f64, tau: f64, d_t: f64)-> Self{
         Self{
             v_th,
@@ -57,11 +126,32 @@ impl LIFNeuron {
             v_reset,
             tau,
             d_t,
+            t_ref: 0u64,
+            reset_mode: ResetMode::ToValue,
+            integration: IntegrationScheme::Exact,
             v_mem: v_rest,
             t_s: 0u64,
-            membrane_error:None
+            refractory: 0u64,
+            membrane_error:None,
+            refractory_error:None
         }
     }
+/// Setta la durata del periodo refrattario assoluto: dopo ogni impulso il neurone ignora
+/// il proprio input per i `t_ref` istanti successivi, mantenendo il potenziale di reset
+    pub fn with_refractory(mut self, t_ref: u64) -> Self {
+        self.t_ref = t_ref;
+        self
+    }
+/// Setta il meccanismo di reset del potenziale di membrana dopo un impulso
+    pub fn with_reset_mode(mut self, reset_mode: ResetMode) -> Self {
+        self.reset_mode = reset_mode;
+        self
+    }
+/// Setta lo schema di integrazione con cui il potenziale di membrana decade verso il potenziale di riposo
+    pub fn with_integration(mut self, integration: IntegrationScheme) -> Self {
+        self.integration = integration;
+        self
+    }
     /*** getters ***/
     pub fn v_rest(&self) -> f64 {
         self.v_rest
@@ -81,18 +171,67 @@ impl LIFNeuron {
     pub fn t_s(&self) -> u64 {
         self.t_s
     }
+    pub fn t_ref(&self) -> u64 {
+        self.t_ref
+    }
+    pub fn reset_mode(&self) -> ResetMode {
+        self.reset_mode
+    }
+    pub fn integration(&self) -> IntegrationScheme {
+        self.integration
+    }
+/// Ritorna la distanza dal potenziale di riposo `v_mem-v_rest` all'istante `t`, dopo gli istanti
+/// trascorsi senza input dall'ultimo aggiornamento, calcolata secondo lo schema di integrazione del neurone
+    fn leak(&self, t: u64, adder: Adder, mult: Multiplier, exp_unit: ExpUnit) -> f64 {
+        let elapsed = t.saturating_sub(self.t_s);
+        /* self.v_mem-self.v_rest */
+        let mut diff = adder.sub(self.v_mem, self.v_rest);
+        match self.integration {
+            IntegrationScheme::Exact => {
+                /* t-self.t_s; in virgola fissa gli istanti sono contatori interi e la loro differenza non passa
+                    dal sommatore (potrebbe non essere rappresentabile nel formato Qm.n), in virgola mobile
+                    passa dal sommatore ed è quindi soggetta ai suoi errori */
+                let elapsed = match adder.format() {
+                    NumericFormat::Fixed { .. } => elapsed as f64,
+                    _ => adder.sub(t as f64, self.t_s as f64),
+                };
+                /* -(elapsed*self.d_t)/self.tau */
+                let exponent = -mult.div(mult.mul(elapsed,self.d_t),self.tau);
+                /* diff*exponent.exp() */
+                mult.mul(diff, exp_unit.exp(exponent))
+            }
+            IntegrationScheme::Euler => {
+                /* self.d_t/self.tau */
+                let rate = mult.div(self.d_t, self.tau);
+                for _ in 0..elapsed {
+                    /* diff - rate*diff */
+                    diff = adder.sub(diff, mult.mul(rate, diff));
+                }
+                diff
+            }
+            IntegrationScheme::ShiftLeak(k) => {
+                /* lo shift è cablato e non passa dal moltiplicatore: diff >> k equivale a diff·2^-k */
+                let scale = 2f64.powi(-(k as i32));
+                for _ in 0..elapsed {
+                    /* diff - (diff >> k) */
+                    diff = adder.sub(diff, diff * scale);
+                }
+                diff
+            }
+        }
+    }
 /// Funzione per controllare la presenza di un errore stuck-at-X su un bit del
 /// potenziale di membrana ed eventualmente forzare tale bit ad X
     fn check_error(&mut self){
-        if self.membrane_error.is_none(){return;}
-        let error=self.membrane_error.as_ref().unwrap();
-        let mask=1u64<<error.position;
-        match error.error_type {
-            /* stuck-at-0 */
-            0 => { self.v_mem = f64::from_bits(self.v_mem.to_bits() & !mask) },
-            /* stuck-at-1 */
-            1=> { self.v_mem = f64::from_bits(self.v_mem.to_bits() | mask) },
-            _=>{}
+        if let Some(error) = self.membrane_error.as_ref(){
+            self.v_mem = error.force_value(self.v_mem);
+        }
+    }
+/// Funzione per controllare la presenza di un errore stuck-at-X su un bit del
+/// contatore del periodo refrattario ed eventualmente forzare tale bit ad X
+    fn check_refractory_error(&mut self){
+        if let Some(error) = self.refractory_error.as_ref(){
+            self.refractory = error.force(self.refractory);
         }
     }
 }
@@ -100,22 +239,39 @@ impl LIFNeuron {
 impl Neuron for LIFNeuron{
     /* in caso di un errore stuck-at-X sul potenziale di membrana, questo errore
         deve essere forzato prima di ciascun utilizzo del potenziale *(i.e. formula e confronto)* */
-    fn update_v_mem(&mut self, t: u64, intra_weight: f64, extra_weight: f64, adder:  Adder, mult:  Multiplier) -> u8 {
+    fn update_v_mem(&mut self, t: u64, intra_weight: f64, extra_weight: f64, adder:  Adder, mult:  Multiplier, exp_unit: ExpUnit, cmp: Comparator) -> u8 {
+        /* periodo refrattario: il contatore viene decrementato degli istanti trascorsi dall'ultimo aggiornamento;
+            se l'istante corrente rientra ancora nel periodo, l'input viene ignorato */
+        let elapsed = t.saturating_sub(self.t_s);
+        self.check_refractory_error();
+        if self.refractory > 0 && self.refractory >= elapsed {
+            self.refractory -= elapsed;
+            self.check_refractory_error();
+            self.t_s = t;
+            return 0;
+        }
+        self.refractory = 0;
+        self.check_refractory_error();
         /* intra_weight+extra_weight */
         let weight_sum = adder.add(intra_weight,extra_weight);
-        /* -(((t-self.t_s)as f64)*self.d_t)/self.tau */
-        let exponent = -mult.div(mult.mul(adder.sub(t as f64, self.t_s as f64),self.d_t as f64),self.tau as f64);
         /* controllo sull'errore su v_mem prima del suo utilizzo */
         self.check_error();
-        /* self.v_rest + (self.v_mem-self.v_rest)*exponent.exp() + weight_sum */
-        self.v_mem = adder.add(adder.add(self.v_rest ,mult.mul(adder.sub(self.v_mem,self.v_rest),exponent.exp())),weight_sum);
+        /* self.v_rest + leak(self.v_mem-self.v_rest) + weight_sum */
+        let leaked = self.leak(t, adder, mult, exp_unit);
+        self.v_mem = adder.add(adder.add(self.v_rest, leaked),weight_sum);
 
         self.t_s = t;
         /* controllo sull'errore su v_mem prima del suo confronto con la soglia */
         self.check_error();
         /* confronto con la soglia ed ritorno del segnale*/
-        return if self.v_mem > self.v_th {
-            self.v_mem = self.v_reset;
+        if cmp.gt(self.v_mem, self.v_th) {
+            self.v_mem = match self.reset_mode {
+                ResetMode::ToValue => self.v_reset,
+                /* self.v_mem-self.v_th */
+                ResetMode::Subtraction => adder.sub(self.v_mem, self.v_th),
+            };
+            self.refractory = self.t_ref;
+            self.check_refractory_error();
             1
         } else {
             0
@@ -128,10 +284,12 @@ impl Neuron for LIFNeuron{
     fn init_neuron(&mut self) {
         self.v_mem= self.v_rest;
         self.t_s = 0u64;
+        self.refractory = 0u64;
         self.membrane_error = None;
+        self.refractory_error = None;
     }
-    fn set_membrane_error(&mut self, error_type:u8, position:u8){
-        self.membrane_error =Some(ErrorBit::new(error_type, position));
+    fn set_membrane_error(&mut self, error_type: u8, mask: u64, format: NumericFormat) {
+        self.membrane_error = Some(ErrorBit::new(error_type, mask).with_format(format));
     }
     fn get_th(&self) -> f64 {
         self.v_th
@@ -148,6 +306,33 @@ impl Neuron for LIFNeuron{
     fn set_mem(&mut self, new_mem: f64){
         self.v_mem = new_mem;
     }
+
+    fn set_refractory_error(&mut self, error_type:u8, mask:u64){
+        self.refractory_error =Some(ErrorBit::new(error_type, mask));
+    }
+
+    fn get_refractory(&self) -> u64 {
+        self.refractory
+    }
+
+    fn set_refractory(&mut self, new_counter: u64){
+        self.refractory = new_counter;
+    }
+
+    fn uses_exp_unit(&self) -> bool {
+        self.integration == IntegrationScheme::Exact
+    }
+
+    fn uses_multiplier(&self) -> bool {
+        !matches!(self.integration, IntegrationScheme::ShiftLeak(_))
+    }
+
+    fn quantize(&mut self, format: NumericFormat) {
+        self.v_th = format.quantize(self.v_th);
+        self.v_rest = format.quantize(self.v_rest);
+        self.v_reset = format.quantize(self.v_reset);
+        self.v_mem = format.quantize(self.v_mem);
+    }
 }
 
 impl Clone for LIFNeuron{
@@ -158,9 +343,124 @@ impl Clone for LIFNeuron{
             v_reset: self.v_reset,
             tau: self.tau,
             d_t: self.d_t,
+            t_ref: self.t_ref,
+            reset_mode: self.reset_mode,
+            integration: self.integration,
             v_mem: self.v_mem,
             t_s: self.t_s,
-            membrane_error:self.membrane_error.clone()
+            refractory: self.refractory,
+            membrane_error:self.membrane_error.clone(),
+            refractory_error:self.refractory_error.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_handling::components::ArithmeticUnits;
+    use crate::error_handling::numeric_format::Overflow;
+
+    /// Neurone con soglia 1, riposo e reset a 0 e *tau* = *d_t* = 1
+    fn neuron() -> LIFNeuron {
+        LIFNeuron::new(1.0, 0.0, 0.0, 1.0, 1.0)
+    }
+    fn step(neuron: &mut LIFNeuron, t: u64, input: f64) -> u8 {
+        let units = ArithmeticUnits::default();
+        neuron.update_v_mem(t, 0.0, input, units.adder, units.mult, units.exp_unit, units.cmp)
+    }
+
+    #[test]
+    fn refractory_period_ignores_input_then_recovers() {
+        let mut n = neuron().with_refractory(2);
+        assert_eq!(step(&mut n, 1, 2.0), 1);
+        assert_eq!(n.get_refractory(), 2);
+        /* per t_ref istanti l'input viene ignorato e il potenziale resta quello di reset */
+        for t in 2..=3 {
+            assert_eq!(step(&mut n, t, 5.0), 0);
+            assert_eq!(n.get_mem(), 0.0);
+        }
+        assert_eq!(n.get_refractory(), 0);
+        assert_eq!(step(&mut n, 4, 2.0), 1);
+        /* istanti senza input (e.g. senza impulsi in ingresso al layer) consumano il periodo refrattario */
+        assert_eq!(step(&mut n, 7, 2.0), 1);
+    }
+
+    #[test]
+    fn refractory_counter_fault() {
+        /* stuck-at-1 sul bit di peso 4: il contatore non si azzera mai e il neurone non genera impulsi */
+        let mut n = neuron().with_refractory(2);
+        n.set_refractory_error(1, 1 << 2);
+        for t in 1..10 {
+            assert_eq!(step(&mut n, t, 5.0), 0);
+        }
+        /* stuck-at-0 sul bit di peso 2: il periodo refrattario di 2 istanti viene annullato */
+        let mut n = neuron().with_refractory(2);
+        n.set_refractory_error(0, 1 << 1);
+        assert_eq!(step(&mut n, 1, 2.0), 1);
+        assert_eq!(n.get_refractory(), 0);
+        assert_eq!(step(&mut n, 2, 2.0), 1);
+    }
+
+    #[test]
+    fn subtraction_reset_keeps_the_residual() {
+        let mut n = neuron().with_reset_mode(ResetMode::Subtraction);
+        assert_eq!(step(&mut n, 1, 2.5), 1);
+        assert_eq!(n.get_mem(), 1.5);
+        /* con un decadimento lento il residuo resta sopra la soglia e genera un secondo impulso senza input */
+        let mut n = LIFNeuron::new(1.0, 0.0, 0.0, 100.0, 1.0).with_reset_mode(ResetMode::Subtraction);
+        assert_eq!(step(&mut n, 1, 2.5), 1);
+        assert_eq!(step(&mut n, 2, 0.0), 1);
+        assert!((n.get_mem() - (1.5 * (-0.01f64).exp() - 1.0)).abs() < 1e-12);
+        assert_eq!(step(&mut n, 3, 0.0), 0);
+        /* con il reset al potenziale v_reset il residuo viene perso */
+        let mut n = LIFNeuron::new(1.0, 0.0, 0.0, 100.0, 1.0);
+        assert_eq!(step(&mut n, 1, 2.5), 1);
+        assert_eq!(n.get_mem(), 0.0);
+        assert_eq!(step(&mut n, 2, 0.0), 0);
+    }
+
+    /// Distanza dal potenziale di riposo dopo `elapsed` istanti senza input, partendo da `v_mem - v_rest` = 1
+    fn leak_after(integration: IntegrationScheme, tau: f64, elapsed: u64) -> f64 {
+        let mut n = LIFNeuron::new(10.0, 0.0, 0.0, tau, 1.0).with_integration(integration);
+        n.set_mem(1.0);
+        let units = ArithmeticUnits::default();
+        n.leak(elapsed, units.adder, units.mult, units.exp_unit)
+    }
+
+    #[test]
+    fn integration_schemes_follow_the_exponential_decay() {
+        /* d_t/tau = 0.01: dopo 100 istanti (= tau) la soluzione esatta vale e^-1 */
+        let exact = leak_after(IntegrationScheme::Exact, 100.0, 100);
+        assert_eq!(exact, (-1f64).exp());
+        /* Eulero: (1 - d_t/tau)^n, vicino alla soluzione esatta per d_t << tau */
+        let euler = leak_after(IntegrationScheme::Euler, 100.0, 100);
+        assert!((euler - 0.99f64.powi(100)).abs() < 1e-12);
+        assert!((euler - exact).abs() / exact < 0.01);
+        /* shift di k = 3 bit, i.e. d_t/tau = 2^-3: ogni passo moltiplica la distanza per 7/8 */
+        let shift = |elapsed| leak_after(IntegrationScheme::ShiftLeak(3), 8.0, elapsed);
+        assert_eq!(shift(1), 0.875);
+        assert_eq!(shift(8), 0.875f64.powi(8));
+        assert!((shift(8) - leak_after(IntegrationScheme::Exact, 8.0, 8)).abs() < 0.03);
+        /* senza istanti trascorsi nessuno schema modifica il potenziale */
+        for integration in [IntegrationScheme::Exact, IntegrationScheme::Euler, IntegrationScheme::ShiftLeak(3)] {
+            assert_eq!(leak_after(integration, 8.0, 0), 1.0);
         }
     }
+
+    #[test]
+    fn elapsed_time_goes_through_the_adder_only_in_floating_point() {
+        let mut n = neuron();
+        n.set_mem(1.0);
+        /* Float64, stuck-at-1 sul bit meno significativo dell'esponente: 1 resta 1, mentre 2 - 0 diventa 4 */
+        let units = ArithmeticUnits::default();
+        let adder = Adder::new(1, 1 << 52);
+        assert_eq!(n.leak(2, adder, units.mult, units.exp_unit), (-4f64).exp());
+        /* Q7.8, stuck-at-1 sul bit di peso 1: 1 resta 1, ma la differenza tra gli istanti non passa dal sommatore */
+        let format = NumericFormat::fixed(7, 8, Overflow::Saturate);
+        let units = ArithmeticUnits::with_format(format);
+        let mut adder = Adder::new(1, 1 << 8);
+        adder.set_format(format);
+        assert_eq!(n.leak(2, adder, units.mult, units.exp_unit), format.quantize((-2f64).exp()));
+    }
 }
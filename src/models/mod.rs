@@ -1 +1,3 @@
-pub mod lifneuron;
\ No newline at end of file
+pub mod lifneuron;
+pub mod alifneuron;
+pub mod izhikevichneuron;
\ No newline at end of file
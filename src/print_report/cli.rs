@@ -0,0 +1,521 @@
+use std::fmt::{Display, Formatter};
+use crate::print_report::menu_handler::{CampaignMode, N_COMPONENTS};
+use crate::error_handling::fault::InjectionConfig;
+use crate::error_handling::components::UnitAllocation;
+use crate::error_handling::error_handling::Upset;
+use crate::error_handling::numeric_format::{BitField, NumericFormat, Overflow};
+use crate::snn::AccumulationMode;
+
+/// Istruzioni d'uso della riga di comando
+pub const USAGE: &str = "\
+Usage:
+  Group12                          interactive menu (default network)
+  Group12 <network file>           interactive menu, network read from the file
+  Group12 <command> [options]
+
+Commands:
+  golden     run the network without faults and write its output
+  run        run a single inference of a campaign (--run, default 0)
+  campaign   run a fault injection campaign
+  report     run again the faults of a saved fault list (--fault-list) and write the report
+  help       print this message
+
+Options:
+  --components <list>      comma separated component indices (0-12) or `all`
+  --error-type <type>      stuck0 | stuck1 | flip (or 0, 1, 2)
+  --error-types <list>     comma separated error types, one drawn at random for each fault (replaces --error-type)
+  --faults <n>             number of inferences of a random campaign
+  --mode <mode>            random | exhaustive | statistical (default random)
+  --margin <percent>       error margin of a statistical campaign (default 1)
+  --confidence <percent>   confidence level of a statistical campaign (default 95)
+  --fault-list <file>      fault list saved by a previous campaign (.toml or .json)
+  --run <index>            index of the inference executed by `run`
+  --network <file>         network definition (.toml or .json)
+  --input <file>           input spikes (.toml or .json)
+  --seed <n>               campaign seed (random if omitted)
+  --output <file>          report file (default report.txt)
+  --save-faults <file>     file of the injected fault list (default faults.toml)
+
+Injection options:
+  --format <format>        float64 | float32 | bf16 | q<m>.<n>[-wrap] (default float64)
+  --fields <list>          comma separated bit fields: sign, exponent, mantissa, integer, fraction
+  --upset <shape>          single | adjacent:<n> | mask:<hex> (default single)
+  --faults-per-run <n>     faults injected in each inference (default 1)
+  --duration <n>           timesteps a transient fault stays on a processing block (default 1)
+  --intermittent <percent> activation probability of intermittent faults at each timestep
+  --allocation <model>     global | per-layer | per-neuron (default global)
+  --accumulation <mode>    protected | extra | intra | all (default all)";
+
+/// Sottocomando richiesto da riga di comando
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// inferenza della rete senza errori
+    Golden,
+    /// singola inferenza di una campagna
+    Run,
+    /// campagna di iniezione degli errori
+    Campaign,
+    /// campagna che ripete gli errori di una lista salvata
+    Report,
+}
+
+/// Opzioni dell'esecuzione, lette da riga di comando o, con il menu interattivo, da stdin
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// componenti in cui iniettare gli errori (stessi indici di `SnnBuilder::build()`)
+    pub components: Vec<i32>,
+    /// tipo di errore; `-1` se non selezionato
+    pub error_index: i32,
+    /// numero di inferenze della campagna casuale
+    pub n_faults: i32,
+    pub mode: CampaignMode,
+    /// seme della campagna; `None` per un seme casuale
+    pub seed: Option<u64>,
+    /// indice dell'unica inferenza da eseguire; `None` per eseguire tutta la campagna
+    pub replay: Option<usize>,
+    /// file di definizione della rete; `None` per la rete predefinita
+    pub network: Option<String>,
+    /// file con gli impulsi in ingresso; `None` per l'input predefinito
+    pub input: Option<String>,
+    /// file del report
+    pub output: String,
+    /// file in cui viene salvata la lista degli errori iniettati
+    pub fault_list_output: String,
+    /// configurazione dell'iniezione degli errori; `None` se non specificata (configurazione predefinita del builder)
+    pub config: Option<InjectionConfig>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            components: vec![],
+            error_index: -1,
+            n_faults: 0,
+            mode: CampaignMode::default(),
+            seed: None,
+            replay: None,
+            network: None,
+            input: None,
+            output: "report.txt".to_string(),
+            fault_list_output: "faults.toml".to_string(),
+            config: None,
+        }
+    }
+}
+
+/// Risultato dell'analisi degli argomenti della riga di comando
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cli {
+    /// nessun sottocomando: viene mostrato il menu interattivo, con la rete eventualmente letta dal file indicato
+    Interactive { network: Option<String> },
+    /// sottocomando con le relative opzioni, eseguito senza leggere da stdin
+    Command(Command, Box<Options>),
+    /// richiesta delle istruzioni d'uso
+    Help,
+}
+
+/// Errori nella riga di comando
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    /// il sottocomando non esiste
+    UnknownCommand(String),
+    /// l'opzione non esiste
+    UnknownFlag(String),
+    /// l'opzione non è seguita da un valore
+    MissingValue(String),
+    /// il valore dell'opzione non è valido
+    InvalidValue { flag: String, value: String },
+    /// l'opzione è obbligatoria per il sottocomando
+    MissingFlag { command: &'static str, flag: &'static str },
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::UnknownCommand(command) => write!(f, "unknown command `{}`", command),
+            CliError::UnknownFlag(flag) => write!(f, "unknown option `{}`", flag),
+            CliError::MissingValue(flag) => write!(f, "option `{}` requires a value", flag),
+            CliError::InvalidValue { flag, value } => write!(f, "invalid value `{}` for option `{}`", value, flag),
+            CliError::MissingFlag { command, flag } => write!(f, "command `{}` requires option `{}`", command, flag),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Analizza gli argomenti della riga di comando (escluso il nome del programma).
+/// Senza argomenti, o con il solo file della rete, ritorna `Cli::Interactive`
+pub fn parse_args(args: &[String]) -> Result<Cli, CliError> {
+    let command = match args.first().map(|arg| arg.as_str()) {
+        None => return Ok(Cli::Interactive { network: None }),
+        Some("help" | "--help" | "-h") => return Ok(Cli::Help),
+        Some("golden") => Command::Golden,
+        Some("run") => Command::Run,
+        Some("campaign") => Command::Campaign,
+        Some("report") => Command::Report,
+        /* compatibilità con l'uso precedente: un solo argomento è il file della rete */
+        Some(path) if args.len() == 1 && (path.ends_with(".toml") || path.ends_with(".json")) =>
+            return Ok(Cli::Interactive { network: Some(path.to_string()) }),
+        Some(command) => return Err(CliError::UnknownCommand(command.to_string())),
+    };
+
+    let mut options = Options::default();
+    let (mut margin, mut confidence) = (0.01, 0.95);
+    let mut statistical = false;
+    let mut fault_list = None;
+    let mut faults = None;
+    let mut run = None;
+    let mut iter = args[1..].iter();
+    while let Some(flag) = iter.next() {
+        let value = iter.next().ok_or_else(|| CliError::MissingValue(flag.clone()))?;
+        let invalid = || CliError::InvalidValue { flag: flag.clone(), value: value.clone() };
+        match flag.as_str() {
+            "--components" => options.components = parse_components(value).ok_or_else(invalid)?,
+            "--error-type" => options.error_index = parse_error_type(value).ok_or_else(invalid)?,
+            "--error-types" => config(&mut options).error_types = parse_error_types(value).ok_or_else(invalid)?,
+            "--faults" => faults = Some(value.parse::<i32>().ok().filter(|&n| n > 0).ok_or_else(invalid)?),
+            "--mode" => match value.as_str() {
+                "random" => { options.mode = CampaignMode::Random; statistical = false; },
+                "exhaustive" => { options.mode = CampaignMode::Exhaustive; statistical = false; },
+                "statistical" => statistical = true,
+                _ => return Err(invalid()),
+            },
+            "--margin" => margin = parse_percentage(value).ok_or_else(invalid)?,
+            "--confidence" => confidence = parse_percentage(value).ok_or_else(invalid)?,
+            "--fault-list" => fault_list = Some(value.clone()),
+            "--run" => run = Some(value.parse::<usize>().map_err(|_| invalid())?),
+            "--network" => options.network = Some(value.clone()),
+            "--input" => options.input = Some(value.clone()),
+            "--seed" => options.seed = Some(value.parse::<u64>().map_err(|_| invalid())?),
+            "--output" => options.output = value.clone(),
+            "--save-faults" => options.fault_list_output = value.clone(),
+            "--format" => config(&mut options).format = parse_format(value).ok_or_else(invalid)?,
+            "--fields" => config(&mut options).fields = parse_fields(value).ok_or_else(invalid)?,
+            "--upset" => config(&mut options).upset = parse_upset(value).ok_or_else(invalid)?,
+            "--faults-per-run" => config(&mut options).fault_count = value.parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(invalid)?,
+            "--duration" => config(&mut options).transient_duration = value.parse::<u64>().ok().filter(|&n| n > 0).ok_or_else(invalid)?,
+            "--intermittent" => config(&mut options).intermittent = Some(value.parse::<f64>().ok()
+                .filter(|&p| p > 0.0 && p <= 100.0).ok_or_else(invalid)? / 100.0),
+            "--allocation" => config(&mut options).allocation = match value.as_str() {
+                "global" => UnitAllocation::Global,
+                "per-layer" => UnitAllocation::PerLayer,
+                "per-neuron" => UnitAllocation::PerNeuron,
+                _ => return Err(invalid()),
+            },
+            "--accumulation" => config(&mut options).accumulation = match value.as_str() {
+                "protected" => AccumulationMode::Protected,
+                "extra" => AccumulationMode::ExtraOnly,
+                "intra" => AccumulationMode::IntraOnly,
+                "all" => AccumulationMode::All,
+                _ => return Err(invalid()),
+            },
+            _ => return Err(CliError::UnknownFlag(flag.clone())),
+        }
+    }
+    if statistical {
+        options.mode = CampaignMode::Statistical { margin, confidence };
+    }
+
+    match command {
+        Command::Golden => {
+            options.components.clear();
+        },
+        Command::Report => {
+            let path = fault_list.ok_or(CliError::MissingFlag { command: "report", flag: "--fault-list" })?;
+            options.mode = CampaignMode::FaultList { path };
+            options.replay = run;
+        },
+        Command::Run | Command::Campaign => {
+            let name = if command == Command::Run { "run" } else { "campaign" };
+            match fault_list {
+                /* una singola inferenza può essere ripetuta anche da una lista di errori */
+                Some(path) if command == Command::Run => options.mode = CampaignMode::FaultList { path },
+                Some(_) => return Err(CliError::UnknownFlag("--fault-list".to_string())),
+                None => {
+                    if options.components.is_empty() {
+                        return Err(CliError::MissingFlag { command: name, flag: "--components" });
+                    }
+                    /* con più tipi di errore il tipo di ciascun errore viene scelto tra quelli indicati */
+                    if options.error_index < 0 && options.config.as_ref().is_none_or(|config| config.error_types.is_empty()) {
+                        return Err(CliError::MissingFlag { command: name, flag: "--error-type" });
+                    }
+                },
+            }
+            if command == Command::Run {
+                let run = run.unwrap_or(0);
+                options.replay = Some(run);
+                /* la campagna casuale deve contenere l'inferenza da eseguire */
+                options.n_faults = faults.unwrap_or(run as i32 + 1);
+            } else {
+                if options.mode == CampaignMode::Random {
+                    options.n_faults = faults.ok_or(CliError::MissingFlag { command: name, flag: "--faults" })?;
+                }
+                options.replay = run;
+            }
+        },
+    }
+    /* il numero di inferenze delle altre campagne è dato dalla popolazione di errori o dalla lista */
+    if faults.is_some() && command != Command::Golden && options.mode != CampaignMode::Random {
+        return Err(CliError::UnknownFlag("--faults".to_string()));
+    }
+    Ok(Cli::Command(command, Box::new(options)))
+}
+
+/// Ritorna la configurazione dell'iniezione delle opzioni, creandola con i valori predefiniti se non ancora specificata
+fn config(options: &mut Options) -> &mut InjectionConfig {
+    options.config.get_or_insert_with(InjectionConfig::default)
+}
+/// Converte il nome del formato numerico (`float64`, `float32`, `bf16` o `q<m>.<n>`, con suffisso `-wrap`
+/// per l'overflow in complemento a 2) nel formato corrispondente
+fn parse_format(value: &str) -> Option<NumericFormat> {
+    match value {
+        "float64" | "f64" => Some(NumericFormat::Float64),
+        "float32" | "f32" => Some(NumericFormat::Float32),
+        "bf16" | "bfloat16" => Some(NumericFormat::BFloat16),
+        _ => {
+            let (q, overflow) = match value.strip_suffix("-wrap") {
+                Some(q) => (q, Overflow::Wrap),
+                None => (value.strip_suffix("-saturate").unwrap_or(value), Overflow::Saturate),
+            };
+            let (int_bits, frac_bits) = q.strip_prefix('q').or_else(|| q.strip_prefix('Q'))?.split_once('.')?;
+            let (int_bits, frac_bits) = (int_bits.parse::<u8>().ok()?, frac_bits.parse::<u8>().ok()?);
+            /* il formato deve stare in una parola di 64 bit, bit di segno compreso */
+            (1 + int_bits as u32 + frac_bits as u32 <= 64).then(|| NumericFormat::fixed(int_bits, frac_bits, overflow))
+        },
+    }
+}
+/// Converte una lista di campi della rappresentazione separati da virgole
+fn parse_fields(value: &str) -> Option<Vec<BitField>> {
+    value.split(',').map(|field| match field.trim() {
+        "sign" => Some(BitField::Sign),
+        "exponent" => Some(BitField::Exponent),
+        "mantissa" => Some(BitField::Mantissa),
+        "integer" => Some(BitField::Integer),
+        "fraction" => Some(BitField::Fraction),
+        _ => None,
+    }).collect()
+}
+/// Converte la forma degli errori (`single`, `adjacent:<n>` o `mask:<maschera esadecimale>`)
+fn parse_upset(value: &str) -> Option<Upset> {
+    match value.split_once(':') {
+        None if value == "single" => Some(Upset::Single),
+        Some(("adjacent", n)) => n.parse::<u8>().ok().filter(|&n| n > 0).map(Upset::Adjacent),
+        Some(("mask", mask)) => u64::from_str_radix(mask.strip_prefix("0x").unwrap_or(mask), 16).ok()
+            .filter(|&mask| mask != 0).map(Upset::Mask),
+        _ => None,
+    }
+}
+/// Converte una lista di indici di componenti separati da virgole (o `all`) nel vettore dei componenti
+fn parse_components(value: &str) -> Option<Vec<i32>> {
+    if value == "all" {
+        return Some((0..N_COMPONENTS).collect());
+    }
+    let mut components = vec![];
+    for component in value.split(',') {
+        let component = component.trim().parse::<i32>().ok().filter(|c| (0..N_COMPONENTS).contains(c))?;
+        if !components.contains(&component) {
+            components.push(component);
+        }
+    }
+    Some(components)
+}
+/// Converte il nome (o l'indice) del tipo di errore nell'indice usato da `SnnBuilder::build()`
+fn parse_error_type(value: &str) -> Option<i32> {
+    match value {
+        "stuck0" | "0" => Some(0),
+        "stuck1" | "1" => Some(1),
+        "flip" | "2" => Some(2),
+        _ => None,
+    }
+}
+/// Converte una lista di tipi di errore separati da virgole, senza ripetizioni
+fn parse_error_types(value: &str) -> Option<Vec<i32>> {
+    let mut error_types = vec![];
+    for error_type in value.split(',') {
+        let error_type = parse_error_type(error_type.trim())?;
+        if !error_types.contains(&error_type) {
+            error_types.push(error_type);
+        }
+    }
+    Some(error_types)
+}
+/// Converte una percentuale strettamente compresa tra 0 e 100 in una frazione
+fn parse_percentage(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|&p| p > 0.0 && p < 100.0).map(|p| p / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Cli, CliError> {
+        let args: Vec<String> = line.split_whitespace().map(|arg| arg.to_string()).collect();
+        parse_args(&args)
+    }
+    /// Ritorna sottocomando e opzioni di una riga di comando valida
+    fn command(line: &str) -> (Command, Options) {
+        match parse(line) {
+            Ok(Cli::Command(command, options)) => (command, *options),
+            other => panic!("Error: `{}` is not a valid command: {:?}", line, other),
+        }
+    }
+
+    #[test]
+    fn interactive_and_help() {
+        assert_eq!(parse(""), Ok(Cli::Interactive { network: None }));
+        assert_eq!(parse("net.toml"), Ok(Cli::Interactive { network: Some("net.toml".to_string()) }));
+        assert_eq!(parse("help"), Ok(Cli::Help));
+        assert_eq!(parse("--help"), Ok(Cli::Help));
+        assert_eq!(parse("simulate"), Err(CliError::UnknownCommand("simulate".to_string())));
+        /* un file della rete seguito da altri argomenti non è un sottocomando */
+        assert_eq!(parse("net.toml --seed 1"), Err(CliError::UnknownCommand("net.toml".to_string())));
+    }
+
+    #[test]
+    fn golden_command() {
+        let (cmd, options) = command("golden --network net.json --input in.toml --output out.txt --components 1,2");
+        assert_eq!(cmd, Command::Golden);
+        assert_eq!(options.network.as_deref(), Some("net.json"));
+        assert_eq!(options.input.as_deref(), Some("in.toml"));
+        assert_eq!(options.output, "out.txt");
+        /* l'inferenza senza errori ignora i componenti */
+        assert!(options.components.is_empty());
+    }
+
+    #[test]
+    fn run_command() {
+        let (cmd, options) = command("run --components 1 --error-type flip --seed 42 --run 3");
+        assert_eq!(cmd, Command::Run);
+        assert_eq!((options.seed, options.replay), (Some(42), Some(3)));
+        /* la campagna casuale contiene l'inferenza da eseguire */
+        assert_eq!(options.n_faults, 4);
+        assert_eq!(command("run --components 1 --error-type flip").1.replay, Some(0));
+        /* una singola inferenza può essere ripetuta da una lista di errori */
+        let options = command("run --fault-list faults.toml --run 2").1;
+        assert_eq!(options.mode, CampaignMode::FaultList { path: "faults.toml".to_string() });
+        assert_eq!(options.replay, Some(2));
+    }
+
+    #[test]
+    fn campaign_command() {
+        let (cmd, options) = command("campaign --components 0,4,4 --error-type stuck1 --faults 10 --save-faults list.json");
+        assert_eq!(cmd, Command::Campaign);
+        assert_eq!(options.components, vec![0, 4]);
+        assert_eq!((options.error_index, options.n_faults), (1, 10));
+        assert_eq!((options.mode, options.replay, options.config), (CampaignMode::Random, None, None));
+        assert_eq!(options.fault_list_output, "list.json");
+        let options = command("campaign --components all --error-type 0 --mode exhaustive").1;
+        assert_eq!(options.components, (0..N_COMPONENTS).collect::<Vec<i32>>());
+        assert_eq!(options.mode, CampaignMode::Exhaustive);
+        let options = command("campaign --components 1 --error-type 2 --mode statistical --margin 2 --confidence 90").1;
+        assert_eq!(options.mode, CampaignMode::Statistical { margin: 0.02, confidence: 0.9 });
+        /* l'ultima modalità indicata prevale */
+        assert_eq!(command("campaign --components 1 --error-type 2 --mode statistical --mode exhaustive").1.mode, CampaignMode::Exhaustive);
+    }
+
+    #[test]
+    fn report_command() {
+        let (cmd, options) = command("report --fault-list faults.toml --network hardened.toml");
+        assert_eq!(cmd, Command::Report);
+        assert_eq!(options.mode, CampaignMode::FaultList { path: "faults.toml".to_string() });
+        assert_eq!(options.network.as_deref(), Some("hardened.toml"));
+        assert_eq!(command("report --fault-list faults.toml --run 5").1.replay, Some(5));
+    }
+
+    #[test]
+    fn missing_and_unknown_flags() {
+        assert_eq!(parse("report"), Err(CliError::MissingFlag { command: "report", flag: "--fault-list" }));
+        assert_eq!(parse("campaign --error-type 1 --faults 3"), Err(CliError::MissingFlag { command: "campaign", flag: "--components" }));
+        assert_eq!(parse("run --components 1"), Err(CliError::MissingFlag { command: "run", flag: "--error-type" }));
+        assert_eq!(parse("campaign --components 1 --error-type 1"), Err(CliError::MissingFlag { command: "campaign", flag: "--faults" }));
+        assert_eq!(parse("campaign --components 1 --seed"), Err(CliError::MissingValue("--seed".to_string())));
+        assert_eq!(parse("golden --verbose yes"), Err(CliError::UnknownFlag("--verbose".to_string())));
+        assert_eq!(parse("campaign --fault-list faults.toml"), Err(CliError::UnknownFlag("--fault-list".to_string())));
+        let invalid = |flag: &str, value: &str| Err(CliError::InvalidValue { flag: flag.to_string(), value: value.to_string() });
+        assert_eq!(parse("golden --seed -1"), invalid("--seed", "-1"));
+        assert_eq!(parse("golden --run x"), invalid("--run", "x"));
+        assert_eq!(parse("campaign --error-type stuck2"), invalid("--error-type", "stuck2"));
+        assert_eq!(parse("campaign --mode sequential"), invalid("--mode", "sequential"));
+        assert_eq!(parse("campaign --margin 100"), invalid("--margin", "100"));
+        assert_eq!(parse("campaign --confidence 0"), invalid("--confidence", "0"));
+    }
+
+    #[test]
+    fn faults_only_in_random_campaigns() {
+        assert_eq!(parse("campaign --components 1 --error-type 1 --faults 0"),
+                   Err(CliError::InvalidValue { flag: "--faults".to_string(), value: "0".to_string() }));
+        let unknown = Err(CliError::UnknownFlag("--faults".to_string()));
+        assert_eq!(parse("campaign --components 1 --error-type 1 --mode exhaustive --faults 3"), unknown);
+        assert_eq!(parse("campaign --components 1 --error-type 1 --mode statistical --faults 3"), unknown);
+        assert_eq!(parse("report --fault-list faults.toml --faults 3"), unknown);
+        assert_eq!(parse("run --fault-list faults.toml --faults 3"), unknown);
+        /* una singola inferenza casuale può indicare la durata della campagna */
+        assert_eq!(command("run --components 1 --error-type 1 --faults 8 --run 2").1.n_faults, 8);
+    }
+
+    #[test]
+    fn injection_options() {
+        let options = command("campaign --components 1 --error-types flip,stuck0,2 --faults 1 --format bf16 --fields sign,exponent \
+            --upset adjacent:2 --faults-per-run 3 --duration 4 --intermittent 25 --allocation per-neuron --accumulation intra").1;
+        /* con più tipi di errore il tipo singolo non è richiesto */
+        assert_eq!(options.error_index, -1);
+        assert_eq!(options.config, Some(InjectionConfig {
+            format: NumericFormat::BFloat16,
+            fields: vec![BitField::Sign, BitField::Exponent],
+            upset: Upset::Adjacent(2),
+            transient_duration: 4,
+            intermittent: Some(0.25),
+            fault_count: 3,
+            error_types: vec![2, 0],
+            allocation: UnitAllocation::PerNeuron,
+            accumulation: AccumulationMode::IntraOnly,
+        }));
+        let invalid = |flag: &str, value: &str| Err(CliError::InvalidValue { flag: flag.to_string(), value: value.to_string() });
+        assert_eq!(parse("golden --faults-per-run 0"), invalid("--faults-per-run", "0"));
+        assert_eq!(parse("golden --duration 0"), invalid("--duration", "0"));
+        assert_eq!(parse("golden --intermittent 0"), invalid("--intermittent", "0"));
+        assert_eq!(parse("golden --intermittent 101"), invalid("--intermittent", "101"));
+        assert_eq!(parse("golden --error-types 0,3"), invalid("--error-types", "0,3"));
+        assert_eq!(parse("golden --fields sign,bits"), invalid("--fields", "sign,bits"));
+        assert_eq!(parse("golden --allocation per-unit"), invalid("--allocation", "per-unit"));
+        assert_eq!(parse("golden --accumulation none"), invalid("--accumulation", "none"));
+    }
+
+    #[test]
+    fn format_parser() {
+        assert_eq!(parse_format("float64"), Some(NumericFormat::Float64));
+        assert_eq!(parse_format("f32"), Some(NumericFormat::Float32));
+        assert_eq!(parse_format("bfloat16"), Some(NumericFormat::BFloat16));
+        assert_eq!(parse_format("q8.8"), Some(NumericFormat::fixed(8, 8, Overflow::Saturate)));
+        assert_eq!(parse_format("Q3.12-wrap"), Some(NumericFormat::fixed(3, 12, Overflow::Wrap)));
+        assert_eq!(parse_format("q3.12-saturate"), Some(NumericFormat::fixed(3, 12, Overflow::Saturate)));
+        /* 64 bit compreso il segno, non oltre */
+        assert_eq!(parse_format("q0.63"), Some(NumericFormat::fixed(0, 63, Overflow::Saturate)));
+        for invalid in ["q32.32", "q200.100", "q8", "q8.", "qa.8", "8.8", "q8.8-clamp", "float16", ""] {
+            assert_eq!(parse_format(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn upset_parser() {
+        assert_eq!(parse_upset("single"), Some(Upset::Single));
+        assert_eq!(parse_upset("adjacent:3"), Some(Upset::Adjacent(3)));
+        assert_eq!(parse_upset("mask:0x5"), Some(Upset::Mask(5)));
+        assert_eq!(parse_upset("mask:ff00"), Some(Upset::Mask(0xff00)));
+        assert_eq!(parse_upset("mask:0xffffffffffffffff"), Some(Upset::Mask(u64::MAX)));
+        for invalid in ["adjacent:0", "adjacent:x", "adjacent", "mask:0", "mask:0x", "mask:zz", "mask:0x1ffffffffffffffff", "double", ""] {
+            assert_eq!(parse_upset(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn list_parsers() {
+        assert_eq!(parse_components("3, 1,3"), Some(vec![3, 1]));
+        assert_eq!(parse_components("13"), None);
+        assert_eq!(parse_components("1,x"), None);
+        assert_eq!(parse_error_types("stuck1,1,flip"), Some(vec![1, 2]));
+        assert_eq!(parse_fields("mantissa, integer"), Some(vec![BitField::Mantissa, BitField::Integer]));
+        assert_eq!(parse_percentage("50"), Some(0.5));
+        assert_eq!(parse_percentage("-5"), None);
+    }
+}
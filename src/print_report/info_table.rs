@@ -1,36 +1,74 @@
 use std::io::Error;
 use std::fs::File;
 use std::io::Write;
-use cli_table::{format::Justify, Cell, Style, Table};
+use std::collections::BTreeMap;
+use cli_table::{format::Justify, Cell, CellStruct, Style, Table};
 use strip_ansi_escapes::strip;
+use crate::error_handling::numeric_format::BitField;
+use crate::error_handling::sampling;
+use crate::error_handling::fault::{Fault, FaultList, FaultRun, InjectionConfig};
 
-/// Struttura per salvare le informazioni di tutti gli errori inseriti nella rete e, per ogni inserimento,
-/// l'accuratezza dell'output della rete con l'errore
+/// Struttura per salvare le informazioni di tutti gli errori inseriti nella rete e, per ogni inferenza,
+/// l'accuratezza dell'output della rete con gli errori; ogni errore è associato all'inferenza (run)
+/// in cui è stato iniettato, che può contenerne più di uno
 #[derive(Debug)]
 pub struct InfoTable{
+    faults: Vec<Fault>,
+    runs: Vec<usize>,
     layers: Vec<usize>,
     neurons: Vec<usize>,
+    weights: Vec<Option<usize>>,
     components: Vec<usize>,
-    bits: Vec<usize>,
+    units: Vec<Option<usize>>,
+    masks: Vec<u64>,
+    fields: Vec<Option<BitField>>,
+    transients: Vec<Option<(u64, u64)>>,
+    fires: Vec<Option<u64>>,
+    values: Vec<Option<(f64, f64)>>,
     error_type: Vec<usize>,
     accuracy: Vec<f64>,
     counter: i32,
-    error_input: Vec<(i32,i32)>
+    error_input: Vec<(i32,i32)>,
+    sampling: Option<(usize, f64)>,
+    /// per ciascun errore, posizione in `accuracy` dell'inferenza in cui è stato iniettato
+    outputs: Vec<usize>,
+    run: usize
+}
+
+impl Default for InfoTable {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InfoTable {
     pub fn new() -> Self {
         Self {
+            faults: vec![],
+            runs: vec![],
             layers: vec![],
             neurons: vec![],
+            weights: vec![],
             components: vec![],
-            bits: vec![],
+            units: vec![],
+            masks: vec![],
+            fields: vec![],
+            transients: vec![],
+            fires: vec![],
+            values: vec![],
             error_type: vec![],
             accuracy: vec![],
             counter: 0,
             error_input: vec![],
+            sampling: None,
+            outputs: vec![],
+            run: 0,
         }
     }
+    /// Aggiunge l'errore iniettato, così come è stato scelto (o letto da file), per la lista degli errori della campagna
+    pub fn add_fault(&mut self, fault: Fault) {
+        self.faults.push(fault);
+    }
     /// Aggiunge l'indice del layer in cui viene iniettato l'errore
     pub fn add_layer(&mut self, layer_index: usize) {
         self.layers.push(layer_index);
@@ -39,19 +77,62 @@ impl InfoTable {
     pub fn add_neuron(&mut self, neuron_index: usize) {
         self.neurons.push(neuron_index);
     }
-    /// Aggiunge il componente in cui viene iniettato l'errore
+    /// Aggiunge l'indice del peso in cui viene iniettato l'errore, ovvero il neurone sorgente della connessione
+    /// (`None` se l'errore non riguarda un peso)
+    pub fn add_weight(&mut self, weight_index: Option<usize>) {
+        self.weights.push(weight_index);
+    }
+    /// Aggiunge il componente in cui viene iniettato l'errore; l'errore viene associato all'inferenza corrente,
+    /// ovvero quella la cui accuratezza non è ancora stata aggiunta con `add_output()`
     pub fn add_component(&mut self, component_index: usize) {
+        self.runs.push(self.run);
+        self.outputs.push(self.accuracy.len());
         self.components.push(component_index);
     }
-    /// Aggiunge l'indice del bit in cui viene iniettato l'errore
-    pub fn add_bit(&mut self, bit_index: usize) {
-        self.bits.push(bit_index);
+    /// Aggiunge l'indice dell'unità di blocchi elaborativi in cui viene iniettato l'errore
+    /// (`None` se l'errore non riguarda un blocco elaborativo)
+    pub fn add_unit(&mut self, unit_index: Option<usize>) {
+        self.units.push(unit_index);
+    }
+    /// Aggiunge la maschera dei bit in cui viene iniettato l'errore (un solo bit a 1 per gli errori su bit singolo)
+    pub fn add_mask(&mut self, mask: u64) {
+        self.masks.push(mask);
+    }
+    /// Aggiunge il campo della rappresentazione (segno, esponente, mantissa, ...) a cui appartiene il bit
+    /// (`None` per i registri interi e l'uscita del comparatore)
+    pub fn add_field(&mut self, field: Option<BitField>) {
+        self.fields.push(field);
+    }
+    /// Aggiunge, per ciascun errore iniettato nell'inferenza, istante iniziale e durata dell'errore transitorio
+    /// (`None` per gli errori permanenti)
+    pub fn add_transients(&mut self, windows: &[Option<(u64, u64)>]) {
+        self.transients.extend_from_slice(windows);
+    }
+    /// Aggiunge, per ciascun errore iniettato nell'inferenza, il numero di istanti in cui l'errore transitorio
+    /// o intermittente si è effettivamente attivato (`None` per gli errori permanenti)
+    pub fn add_fires(&mut self, fires: &[Option<u64>]) {
+        self.fires.extend_from_slice(fires);
+    }
+    /// Aggiunge, per ciascun errore iniettato nell'inferenza, il valore originale e il valore corrotto del registro
+    /// colpito (`None` se non disponibili, e.g. per i blocchi elaborativi)
+    pub fn add_values(&mut self, values: &[Option<(f64, f64)>]) {
+        self.values.extend_from_slice(values);
     }
     /// Aggiunge il tipo di errore che viene iniettato
     pub fn add_error_type(&mut self, error_type: usize) {
         self.error_type.push(error_type);
     }
-    /// Aggiunge accuratezza dell'output
+    /// Setta l'indice, nella campagna, dell'inferenza corrente (e.g. l'inferenza riprodotta da una lista di errori);
+    /// gli errori aggiunti successivamente vengono associati a questa inferenza
+    pub fn set_run(&mut self, run: usize) {
+        self.run = run;
+    }
+    /// Indica che gli errori sono un campione casuale di una popolazione di `population` errori:
+    /// il riassunto riporta l'intervallo di confidenza, al livello `confidence`, della percentuale di inferenze affette
+    pub fn set_sampling(&mut self, population: usize, confidence: f64) {
+        self.sampling = Some((population, confidence));
+    }
+    /// Aggiunge accuratezza dell'output dell'inferenza corrente, chiudendola
     pub fn add_output(&mut self, acc: f64) {
         if acc != 0.0 {
             self.counter += 1;
@@ -70,7 +151,7 @@ impl InfoTable {
         }
 
     }
-    pub fn print_no_error<const SNN_OUTPUT_DIM: usize, const SPIKES_DURATION: usize, const SNN_INPUT_DIM: usize>(&self, file: &mut File,snn_result_0_error: &[[u8; SNN_OUTPUT_DIM]; SPIKES_DURATION],  snn_input: &[[u8; SNN_INPUT_DIM]; SPIKES_DURATION])->Result<(),Error>{
+    pub fn print_no_error(&self, file: &mut File,snn_result_0_error: &[Vec<u8>],  snn_input: &[Vec<u8>])->Result<(),Error>{
         println!("#######################################################");
         println!("#                  SNN WITHOUT ERROR                  #");
         println!("#######################################################");
@@ -91,10 +172,15 @@ impl InfoTable {
     }
     /// Stampa su file una tabella con tutte le informazioni sugli errori
     pub fn print_table(&mut self, file: &mut File) -> Result<(), Error> {
-        let multiplier = 10_f64.powi(2);
+        if self.accuracy.is_empty() {
+            /* campagna vuota (popolazione esaustiva o lista di errori senza inferenze) */
+            println!("no runs");
+            writeln!(file, "no runs")?;
+            return Ok(());
+        }
         let mut best_indecies: Vec<usize>= vec![];
         let max_impact = self.accuracy.clone().into_iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-        let impacted_inferences = 100 as f64 * self.accuracy.clone().into_iter().filter(|&x| x > 0.0).count() as f64 / self.layers.len() as f64;
+        let impacted_inferences = 100_f64 * self.accuracy.clone().into_iter().filter(|&x| x > 0.0).count() as f64 / self.accuracy.len() as f64;
         let non_zero_values: Vec<f64> = self.accuracy.clone().into_iter().filter(|&x| x != 0.0).collect();
 
         let mut avarage_impact = 0.0;
@@ -103,33 +189,15 @@ impl InfoTable {
             avarage_impact = sum / non_zero_values.len() as f64;
         }
         if max_impact != 0.0{
-            best_indecies = self.accuracy.iter().enumerate().filter(|&(_, &x)| x == max_impact).map(|(index, _)| index).collect();
+            /* tutti gli errori delle inferenze con l'impatto maggiore */
+            best_indecies = (0..self.layers.len()).filter(|&n| self.impact(n) == max_impact).collect();
         }
         let len = self.layers.len();
         let mut table = vec![];
         for n in 0..len {
-            let truncated_imp = (self.accuracy[n]* multiplier).floor() / multiplier;
-            let mut layer= self.layers[n].cell().justify(Justify::Right);
-            let mut neuron = self.neurons[n].cell().justify(Justify::Right);
-            if  self.components[n] == 4 || self.components[n] == 5 ||  self.components[n] == 6 || self.components[n] == 7 {
-               layer = "/".cell().justify(Justify::Right);
-                neuron="/".cell().justify(Justify::Right);
-            }
-            let mut input = from_index_to_str_component(self.components[n]).cell().justify(Justify::Left);
-            if self.components[n] == 5 || self.components[n] == 7{
-                input =(from_index_to_str_component(self.components[n]).to_string() +" - ("+ &*self.error_input[n].0.to_string() +","+ &*self.error_input[n].1.to_string() +")").cell().justify(Justify::Left);
-
-            }
-
-            table.push(vec![layer,
-                            neuron,
-                            input,
-                            self.bits[n].cell().justify(Justify::Right),
-                            from_index_to_str_error(self.error_type[n] ).cell().justify(Justify::Left),
-                            (truncated_imp.to_string() + "%").cell().justify(Justify::Right)
-            ])
+            table.push(self.fault_row(n));
         }
-        let table_complete = table.table().title(vec!["Layer".cell().bold(true), "Neuron".cell().bold(true), "Component".cell().bold(true), "Bit".cell().bold(true), "Error".cell().bold(true), "Impact On Accuracy".cell().bold(true)]);
+        let table_complete = table.table().title(fault_title());
         let table_display = table_complete.display().unwrap();
 
 
@@ -141,71 +209,221 @@ impl InfoTable {
         let stripped_table = String::from_utf8_lossy(&stripped_bytes);
         file.write_all(stripped_table.as_bytes()).expect("TEST");
         if max_impact != 0.0{
-            print_max_impact_info(file,self.layers.clone(), self.neurons.clone(), self.components.clone(), self.bits.clone(), self.error_type.clone(), self.accuracy.clone(), self.error_input.clone(), best_indecies.clone()).expect("Unable to write");
+            self.print_max_impact_info(file, best_indecies).expect("Unable to write");
         }
-        print_summary_table(file, self.counter, impacted_inferences, max_impact, avarage_impact).expect("Error");
+        let interval = self.sampling.map(|(population, confidence)| {
+            let (low, high) = sampling::confidence_interval(self.counter as usize, self.accuracy.len(), population, confidence);
+            (100_f64 * low, 100_f64 * high, 100_f64 * confidence)
+        });
+        print_summary_table(file, self.counter, impacted_inferences, max_impact, avarage_impact, interval).expect("Error");
+        self.print_field_summary(file).expect("Error");
+        self.print_vulnerability_map(file).expect("Error");
 
         Ok(())
     }
-}
-fn print_max_impact_info(file: &mut File, layers: Vec<usize>, neurons: Vec<usize>, components: Vec<usize>, bits:Vec<usize>, error_type: Vec<usize>, accuracy: Vec<f64>, error_input: Vec<(i32, i32)>, best_indecies: Vec<usize>)->Result<(),Error>{
-    println!("\n######################################################################################");
-    println!("#                                   MAX IMPACT INFO                                  #");
-    println!("######################################################################################");
-    let multiplier = 10_f64.powi(2);
-    let mut table = vec![];
-
-    for n in best_indecies {
-        let truncated_imp = (accuracy[n]* multiplier).floor() / multiplier;
-        let mut layer= layers[n].cell().justify(Justify::Right);
-        let mut neuron = neurons[n].cell().justify(Justify::Right);
-        if  components[n] == 4 || components[n] == 5 ||  components[n] == 6 || components[n] == 7 {
+    /// Stampa su file la mappa di vulnerabilità della rete: gli errori vengono raggruppati per componente e layer
+    /// (per i blocchi elaborativi, per unità), riportando numero di errori, percentuale di errori che hanno
+    /// modificato l'output e impatto medio; con una campagna esaustiva la mappa copre tutti i bit della rete
+    fn print_vulnerability_map(&self, file: &mut File) -> Result<(), Error> {
+        let mut groups: BTreeMap<(usize, Option<usize>, Option<usize>), Vec<f64>> = BTreeMap::new();
+        for n in 0..self.components.len() {
+            let layer = match is_arithmetic_unit(self.components[n]) {
+                true => None,
+                false => Some(self.layers[n]),
+            };
+            groups.entry((self.components[n], layer, self.units[n])).or_default().push(self.impact(n));
+        }
+        if groups.is_empty() {
+            return Ok(());
+        }
+        println!("\n######################################################################################");
+        println!("#                                  VULNERABILITY MAP                                 #");
+        println!("######################################################################################");
+        let multiplier = 10_f64.powi(2);
+        let mut table = vec![];
+        for ((component, layer, unit), impacts) in groups {
+            let affected: Vec<f64> = impacts.iter().copied().filter(|&x| x != 0.0).collect();
+            let affected_perc = 100_f64 * affected.len() as f64 / impacts.len() as f64;
+            let mut average_impact = 0.0;
+            if !affected.is_empty() {
+                average_impact = affected.iter().sum::<f64>() / affected.len() as f64;
+            }
+            table.push(vec![from_index_to_str_component(component).cell().justify(Justify::Left),
+                            from_option_to_str(layer.map(|l| l as u64)).cell().justify(Justify::Right),
+                            from_option_to_str(unit.map(|u| u as u64)).cell().justify(Justify::Right),
+                            impacts.len().cell().justify(Justify::Right),
+                            (((affected_perc * multiplier).floor() / multiplier).to_string() + "%").cell().justify(Justify::Right),
+                            (((average_impact * multiplier).floor() / multiplier).to_string() + "%").cell().justify(Justify::Right)
+            ])
+        }
+        let table_complete = table.table().title(vec!["Component".cell().bold(true), "Layer".cell().bold(true), "Unit".cell().bold(true), "Faults".cell().bold(true), "Affected Faults %".cell().bold(true), "Average Impact On Accuracy".cell().bold(true)]);
+        println!("{}", table_complete.display().unwrap());
+        writeln!(file,"\n######################################################################################")?;
+        writeln!(file,"#                                  VULNERABILITY MAP                                 #")?;
+        writeln!(file,"######################################################################################")?;
+        let stripped_bytes = strip(table_complete.display().unwrap().to_string());
+        let stripped_table = String::from_utf8_lossy(&stripped_bytes);
+        file.write_all(stripped_table.as_bytes()).expect("TEST");
+        Ok(())
+    }
+    /// Ritorna la lista esatta degli errori iniettati, raggruppati per inferenza; per gli errori transitori
+    /// viene riportato l'istante iniziale effettivo, in modo che ripetendo la lista venga iniettato nello stesso istante.
+    /// `config` è la configurazione dell'iniezione del builder con cui gli errori sono stati iniettati
+    /// e `seed` il seme della campagna
+    pub fn fault_list(&self, config: InjectionConfig, seed: u64) -> FaultList {
+        let mut list = FaultList { seed: Some(seed), config, runs: vec![] };
+        for (n, fault) in self.faults.iter().enumerate() {
+            let mut fault = *fault;
+            if let Some(Some((start, _))) = self.transients.get(n) {
+                fault.instant = Some(*start);
+            }
+            match list.runs.last_mut() {
+                Some(last) if last.run == self.runs[n] => last.faults.push(fault),
+                _ => list.runs.push(FaultRun { run: self.runs[n], faults: vec![fault] }),
+            }
+        }
+        list
+    }
+    /// Ritorna la riga della tabella con le informazioni dell'errore `n`
+    fn fault_row(&self, n: usize) -> Vec<CellStruct> {
+        let multiplier = 10_f64.powi(2);
+        let truncated_imp = (self.impact(n)* multiplier).floor() / multiplier;
+        let mut layer= self.layers[n].cell().justify(Justify::Right);
+        let mut neuron = self.neurons[n].cell().justify(Justify::Right);
+        if is_arithmetic_unit(self.components[n]) {
             layer = "/".cell().justify(Justify::Right);
             neuron="/".cell().justify(Justify::Right);
         }
-        let mut input = from_index_to_str_component(components[n]).cell().justify(Justify::Left);
-        if components[n] == 5 || components[n] == 7{
-            input =(from_index_to_str_component(components[n]).to_string() +" - ("+ &*error_input[n].0.to_string() +","+ &*error_input[n].1.to_string() +")").cell().justify(Justify::Left);
+        let mut input = from_index_to_str_component(self.components[n]).cell().justify(Justify::Left);
+        if self.components[n] == 5 || self.components[n] == 7{
+            input =(from_index_to_str_component(self.components[n]).to_string() +" - ("+ &*self.error_input[n].0.to_string() +","+ &*self.error_input[n].1.to_string() +")").cell().justify(Justify::Left);
 
         }
 
-        table.push(vec![layer,
-                        neuron,
-                        input,
-                        bits[n].cell().justify(Justify::Right),
-                        from_index_to_str_error(error_type[n] ).cell().justify(Justify::Left),
-                        (truncated_imp.to_string() + "%").cell().justify(Justify::Right)
-        ])
+        let unit = match self.units[n] {
+            Some(unit) => unit.cell().justify(Justify::Right),
+            None => "/".cell().justify(Justify::Right),
+        };
+
+        vec![self.runs[n].cell().justify(Justify::Right),
+             layer,
+             neuron,
+             from_option_to_str(self.weights[n].map(|w| w as u64)).cell().justify(Justify::Right),
+             unit,
+             input,
+             format!("{:#x}", self.masks[n]).cell().justify(Justify::Right),
+             from_field_to_str(self.fields[n]).cell().justify(Justify::Left),
+             from_index_to_str_error(self.error_type[n] ).cell().justify(Justify::Left),
+             from_option_to_str(self.transients.get(n).copied().flatten().map(|(start, _)| start)).cell().justify(Justify::Right),
+             from_option_to_str(self.transients.get(n).copied().flatten().map(|(_, duration)| duration)).cell().justify(Justify::Right),
+             from_option_to_str(self.fires.get(n).copied().flatten()).cell().justify(Justify::Right),
+             self.value_to_str(n, |(original, _)| original).cell().justify(Justify::Right),
+             self.value_to_str(n, |(_, corrupted)| corrupted).cell().justify(Justify::Right),
+             (truncated_imp.to_string() + "%").cell().justify(Justify::Right)
+        ]
+    }
+    /// Ritorna il valore (originale o corrotto, secondo `select`) del registro colpito dall'errore `n` come stringa,
+    /// "not injected" se l'errore transitorio o intermittente non è mai stato applicato
+    fn value_to_str(&self, n: usize, select: fn((f64, f64)) -> f64) -> String {
+        match self.fires.get(n).copied().flatten() {
+            Some(0) => "not injected".to_string(),
+            _ => from_value_to_str(self.values.get(n).copied().flatten().map(select)),
+        }
     }
+    /// Ritorna l'impatto sull'accuratezza dell'inferenza in cui è stato iniettato l'errore `n`
+    fn impact(&self, n: usize) -> f64 {
+        self.accuracy[self.outputs[n]]
+    }
+    /// Stampa su file le informazioni sugli errori che hanno avuto l'impatto maggiore sull'accuratezza
+    fn print_max_impact_info(&self, file: &mut File, best_indecies: Vec<usize>)->Result<(),Error>{
+        println!("\n######################################################################################");
+        println!("#                                   MAX IMPACT INFO                                  #");
+        println!("######################################################################################");
+        let mut table = vec![];
 
+        for n in best_indecies {
+            table.push(self.fault_row(n));
+        }
 
-    let table_complete = table.table().title(vec!["Layer".cell().bold(true), "Neuron".cell().bold(true), "Component".cell().bold(true), "Bit".cell().bold(true), "Error".cell().bold(true), "Impact On Accuracy".cell().bold(true)]);
-    let table_display = table_complete.display().unwrap();
-    print!("{}", table_display);
 
-    writeln!(file,"\n######################################################################################")?;
-    writeln!(file,"#                                   MAX IMPACT INFO                                  #")?;
-    writeln!(file,"######################################################################################")?;
-    let stripped_bytes = strip(table_complete.display().unwrap().to_string());
-    let stripped_table = String::from_utf8_lossy(&stripped_bytes);
-    file.write_all(stripped_table.as_bytes()).expect("TEST");
-    Ok(())
+        let table_complete = table.table().title(fault_title());
+        let table_display = table_complete.display().unwrap();
+        print!("{}", table_display);
+
+        writeln!(file,"\n######################################################################################")?;
+        writeln!(file,"#                                   MAX IMPACT INFO                                  #")?;
+        writeln!(file,"######################################################################################")?;
+        let stripped_bytes = strip(table_complete.display().unwrap().to_string());
+        let stripped_table = String::from_utf8_lossy(&stripped_bytes);
+        file.write_all(stripped_table.as_bytes()).expect("TEST");
+        Ok(())
 
 
+    }
+    /// Stampa su file un riassunto dell'impatto degli errori raggruppati per campo della rappresentazione:
+    /// numero di errori, percentuale di inferenze affette e impatto medio sulle inferenze affette
+    fn print_field_summary(&self, file: &mut File) -> Result<(), Error> {
+        let mut fields: Vec<BitField> = self.fields.iter().flatten().copied().collect();
+        fields.sort();
+        fields.dedup();
+        if fields.is_empty() {
+            return Ok(());
+        }
+        println!("\n######################################################################################");
+        println!("#                                    FIELD SUMMARY                                   #");
+        println!("######################################################################################");
+        let multiplier = 10_f64.powi(2);
+        let mut table = vec![];
+        for field in fields {
+            let impacts: Vec<f64> = (0..self.fields.len())
+                .filter(|&n| self.fields[n] == Some(field))
+                .map(|n| self.impact(n))
+                .collect();
+            let affected: Vec<f64> = impacts.iter().copied().filter(|&x| x != 0.0).collect();
+            let affected_perc = 100_f64 * affected.len() as f64 / impacts.len() as f64;
+            let mut average_impact = 0.0;
+            if !affected.is_empty() {
+                average_impact = affected.iter().sum::<f64>() / affected.len() as f64;
+            }
+            table.push(vec![field.to_string().cell().justify(Justify::Left),
+                            impacts.len().cell().justify(Justify::Right),
+                            (((affected_perc * multiplier).floor() / multiplier).to_string() + "%").cell().justify(Justify::Right),
+                            (((average_impact * multiplier).floor() / multiplier).to_string() + "%").cell().justify(Justify::Right)
+            ])
+        }
+        let table_complete = table.table().title(vec!["Field".cell().bold(true), "Faults".cell().bold(true), "Affected Inferences %".cell().bold(true), "Average Impact On Accuracy".cell().bold(true)]);
+        println!("{}", table_complete.display().unwrap());
+        writeln!(file,"\n######################################################################################")?;
+        writeln!(file,"#                                    FIELD SUMMARY                                   #")?;
+        writeln!(file,"######################################################################################")?;
+        let stripped_bytes = strip(table_complete.display().unwrap().to_string());
+        let stripped_table = String::from_utf8_lossy(&stripped_bytes);
+        file.write_all(stripped_table.as_bytes()).expect("TEST");
+        Ok(())
+    }
 }
-fn print_summary_table(file: &mut File, tot_inf: i32, impacted_inf: f64, max_impact: f64, avarge_impact: f64)->Result<(), Error>{
+/// Stampa su file il riassunto della campagna; `interval` contiene gli estremi dell'intervallo di confidenza
+/// della percentuale di inferenze affette e il relativo livello di confidenza, tutti in percentuale (solo per campagne statistiche)
+fn print_summary_table(file: &mut File, tot_inf: i32, impacted_inf: f64, max_impact: f64, avarge_impact: f64, interval: Option<(f64, f64, f64)>)->Result<(), Error>{
     println!("\n######################################################################################");
     println!("#                                       SUMMARY                                      #");
     println!("######################################################################################");
     let multiplier = 10_f64.powi(2);
     let truncated_max = (max_impact * multiplier).floor() / multiplier;
     let truncated_avg = (avarge_impact * multiplier).floor() / multiplier;
-    let mut table = vec![];
-    table.push(vec![tot_inf.cell().justify(Justify::Right)
+    let mut row = vec![tot_inf.cell().justify(Justify::Right)
                             ,(impacted_inf.to_string()+"%").cell().justify(Justify::Right),
                     (truncated_max.to_string() +"%").cell().justify(Justify::Right),
-                    (truncated_avg.to_string() +"%").cell().justify(Justify::Right)]);
-    let table_complete = table.table().title(vec!["Total Affected Inferences".cell().bold(true), "Total Affected Inferences %".cell().bold(true), "Max Impact On Accuracy".cell().bold(true), "Average Impact On Accuracy".cell().bold(true)]);
+                    (truncated_avg.to_string() +"%").cell().justify(Justify::Right)];
+    let mut title = vec!["Total Affected Inferences".cell().bold(true), "Total Affected Inferences %".cell().bold(true), "Max Impact On Accuracy".cell().bold(true), "Average Impact On Accuracy".cell().bold(true)];
+    if let Some((low, high, confidence)) = interval {
+        let truncated_low = (low * multiplier).floor() / multiplier;
+        let truncated_high = (high * multiplier).ceil() / multiplier;
+        row.push(format!("[{}%, {}%]", truncated_low, truncated_high).cell().justify(Justify::Right));
+        title.push(format!("Affected Inferences {}% CI", confidence).cell().bold(true));
+    }
+    let table = vec![row];
+    let table_complete = table.table().title(title);
     println!("{}", table_complete.display().unwrap());
     writeln!(file,"\n######################################################################################")?;
     writeln!(file,"#                                       SUMMARY                                      #")?;
@@ -217,6 +435,39 @@ fn print_summary_table(file: &mut File, tot_inf: i32, impacted_inf: f64, max_imp
 
 }
 
+/// Ritorna `true` se il componente appartiene a un blocco elaborativo condiviso della rete
+/// (sommatore, moltiplicatore, unità esponenziale, comparatore), per cui layer e neurone non sono significativi
+fn is_arithmetic_unit(component: usize) -> bool {
+    matches!(component, 4..=7 | 10..=12)
+}
+/// Intestazione delle tabelle con le informazioni sugli errori
+fn fault_title() -> Vec<CellStruct> {
+    vec!["Run".cell().bold(true), "Layer".cell().bold(true), "Neuron".cell().bold(true), "Weight".cell().bold(true), "Unit".cell().bold(true),
+         "Component".cell().bold(true), "Mask".cell().bold(true), "Field".cell().bold(true), "Error".cell().bold(true), "Start".cell().bold(true),
+         "Duration".cell().bold(true), "Fires".cell().bold(true), "Original".cell().bold(true), "Corrupted".cell().bold(true), "Impact On Accuracy".cell().bold(true)]
+}
+/// Ritorna il valore come stringa, in notazione esponenziale se molto grande o molto piccolo
+/// (e.g. dopo un errore sull'esponente), "/" se non presente
+fn from_value_to_str(value: Option<f64>) -> String {
+    match value {
+        Some(value) if value == 0.0 || (1e-4..1e6).contains(&value.abs()) => value.to_string(),
+        Some(value) => format!("{:e}", value),
+        None => "/".to_string(),
+    }
+}
+fn from_field_to_str(field: Option<BitField>) -> String {
+    match field {
+        Some(field) => field.to_string(),
+        None => "/".to_string(),
+    }
+}
+/// Ritorna il valore opzionale come stringa, "/" se non presente (e.g. durata di un errore permanente)
+fn from_option_to_str(value: Option<u64>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "/".to_string(),
+    }
+}
 fn from_index_to_str_error(index: usize) -> &'static str {
     match index{
         0=>"Stack-At-0",
@@ -235,6 +486,11 @@ fn from_index_to_str_component(index: usize) -> &'static str {
         5=>"Adder Input",
         6=>"Multiplier Output",
         7=>"Multiplier Input",
+        8=>"Refractory Counter",
+        9=>"Auxiliary Variable",
+        10=>"Exp Unit Output",
+        11=>"Exp Unit Input",
+        12=>"Comparator Output",
         _ => "None"
     }
 }
@@ -1,12 +1,49 @@
 use std::fs::File;
 use std::io::Write;
 use std::io::Error;
+use std::fmt::{Display, Formatter};
 
-pub fn print_menu(components: &mut Vec<i32>, error_index: &mut i32, n_faults:&mut  i32){
+/// Numero di componenti selezionabili; l'indice `N_COMPONENTS` seleziona tutti i componenti
+pub const N_COMPONENTS: i32 = 13;
+
+/// Modalità con cui vengono scelti gli errori iniettati dalla campagna
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CampaignMode {
+    /// `n_faults` errori scelti casualmente
+    #[default]
+    Random,
+    /// tutti gli errori possibili sui componenti selezionati, enumerati in modo riproducibile
+    Exhaustive,
+    /// un campione casuale degli errori possibili, di dimensione sufficiente a stimare la percentuale di inferenze
+    /// affette con margine di errore `margin` e livello di confidenza `confidence` (entrambi in (0, 1))
+    Statistical { margin: f64, confidence: f64 },
+    /// gli errori della lista salvata nel file `path` da una campagna precedente, inferenza per inferenza
+    FaultList { path: String },
+}
+
+impl Display for CampaignMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CampaignMode::Random => write!(f, "Random"),
+            CampaignMode::Exhaustive => write!(f, "Exhaustive"),
+            CampaignMode::Statistical { margin, confidence } =>
+                write!(f, "Statistical (margin {}%, confidence {}%)", margin * 100.0, confidence * 100.0),
+            CampaignMode::FaultList { path } => write!(f, "Fault list ({})", path),
+        }
+    }
+}
+
+pub fn print_menu(components: &mut Vec<i32>, error_index: &mut i32, error_types: &mut Vec<i32>, n_faults:&mut  i32,
+                  mode: &mut CampaignMode, seed: &mut Option<u64>, replay: &mut Option<usize>){
     print_components_menu(components);
-    if components.len() != 0 {
-        print_error_menu(error_index);
-        print_n_fault_menu(n_faults);
+    if !components.is_empty() {
+        print_error_menu(error_index, error_types);
+        print_campaign_menu(mode);
+        /* nelle altre modalità il numero di errori dipende dai componenti selezionati (o dalla lista di errori) */
+        if *mode == CampaignMode::Random {
+            print_n_fault_menu(n_faults);
+        }
+        print_seed_menu(seed, replay);
     }
 }
 pub fn print_components_menu(componets: &mut Vec<i32>){
@@ -23,7 +60,12 @@ pub fn print_components_menu(componets: &mut Vec<i32>){
     println!("#         5 => Adder Input                            #");
     println!("#         6 => Multiplier Output                      #");
     println!("#         7 => Multiplier Input                       #");
-    println!("#         8 => All Components                         #");
+    println!("#         8 => Refractory Counter                     #");
+    println!("#         9 => Auxiliary Variable (e.g. u, theta)     #");
+    println!("#        10 => Exp Unit Output                        #");
+    println!("#        11 => Exp Unit Input                         #");
+    println!("#        12 => Comparator Output                      #");
+    println!("#        13 => All Components                         #");
     println!("#                                                     #");
     println!("#######################################################");
     println!("Insert digit to select component to verify! - (-1 to end components selection)");
@@ -35,7 +77,7 @@ pub fn print_components_menu(componets: &mut Vec<i32>){
 
         let trimmed_input = input.trim();
 
-        if trimmed_input == "-1" && componets.len() == 0{
+        if trimmed_input == "-1" && componets.is_empty(){
             println!("No components selected, running without components error");
             break;
         }
@@ -47,13 +89,13 @@ pub fn print_components_menu(componets: &mut Vec<i32>){
 
         match trimmed_input.parse::<i32>() {
             Ok(number) => {
-                if number<0 || number>8{
+                if !(0..=N_COMPONENTS).contains(&number){
                     println!("Invalid component digit, try another one!");
                 }else if componets.contains(&number){
                     println!("Components already inserted!, try another one!");
-                }else if number == 8{
+                }else if number == N_COMPONENTS{
                     componets.clear();
-                    for i in 0..8{
+                    for i in 0..N_COMPONENTS{
                             componets.push(i);
                         }
                     println!("All components selected");
@@ -61,7 +103,7 @@ pub fn print_components_menu(componets: &mut Vec<i32>){
                     break;
                 }else{
                     componets.push(number);
-                    if componets.len() == 8 {
+                    if componets.len() == N_COMPONENTS as usize {
                         break;
                     }
                 }
@@ -74,13 +116,16 @@ pub fn print_components_menu(componets: &mut Vec<i32>){
     }
 
 }
-pub fn print_error_menu(error_index: &mut i32){
+/// Chiede il tipo di errore; con `3` chiede invece la lista dei tipi tra cui scegliere casualmente
+/// il tipo di ciascun errore (salvata in `error_types`, mentre `error_index` resta non selezionato)
+pub fn print_error_menu(error_index: &mut i32, error_types: &mut Vec<i32>){
     println!("#######################################################");
     println!("#                                                     #");
     println!("#       Error Type:                                   #");
     println!("#         0 => Stuck-at-0                             #");
     println!("#         1 => Stuck-at-1                             #");
     println!("#         2 => Flip-bit                               #");
+    println!("#         3 => Mixed (random type for each fault)     #");
     println!("#                                                     #");
     println!("#######################################################");
     println!("Insert digit to select an error!");
@@ -94,8 +139,11 @@ pub fn print_error_menu(error_index: &mut i32){
 
         match trimmed_input.parse::<i32>() {
             Ok(number) => {
-                if number<0 || number>2{
+                if !(0..=3).contains(&number){
                     println!("Invalid error digit, try another one!");
+                }else if number == 3{
+                    *error_types = read_error_types();
+                    break;
                 }else{
                     *error_index = number;
                     break;
@@ -108,6 +156,102 @@ pub fn print_error_menu(error_index: &mut i32){
     }
 
 }
+pub fn print_campaign_menu(mode: &mut CampaignMode){
+    println!("#######################################################");
+    println!("#                                                     #");
+    println!("#       Campaign:                                     #");
+    println!("#         0 => Random faults                          #");
+    println!("#         1 => Exhaustive (every bit of every target) #");
+    println!("#         2 => Statistical (error margin, confidence) #");
+    println!("#         3 => Fault list file (previous campaign)    #");
+    println!("#                                                     #");
+    println!("#######################################################");
+    println!("Insert digit to select the campaign mode!");
+    loop {
+        let mut input = String::new();
+        println!("> ");
+        std::io::stdin().read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim().parse::<i32>() {
+            Ok(0) => {
+                *mode = CampaignMode::Random;
+                break;
+            },
+            Ok(1) => {
+                *mode = CampaignMode::Exhaustive;
+                break;
+            },
+            Ok(2) => {
+                let margin = read_percentage("Insert the target error margin (%), e.g. 1");
+                let confidence = read_percentage("Insert the confidence level (%), e.g. 95");
+                *mode = CampaignMode::Statistical { margin, confidence };
+                break;
+            },
+            Ok(3) => {
+                println!("Insert the path of the fault list file (.toml or .json)!");
+                let path = loop {
+                    if let Some(path) = read_optional::<String>() {
+                        break path;
+                    }
+                };
+                *mode = CampaignMode::FaultList { path };
+                break;
+            },
+            Ok(_) => {
+                println!("Invalid campaign digit, try another one!");
+            },
+            Err(_) => {
+                println!("Failed to convert digit, insert another value");
+            }
+        }
+    }
+
+}
+/// Legge da stdin una lista non vuota di tipi di errore (`0`, `1`, `2`) separati da virgole, senza ripetizioni
+fn read_error_types() -> Vec<i32> {
+    println!("Insert the error types separated by commas, e.g. 0,2");
+    loop {
+        let mut input = String::new();
+        println!("> ");
+        std::io::stdin().read_line(&mut input)
+            .expect("Failed to read line");
+
+        let error_types: Option<Vec<i32>> = input.trim().split(',')
+            .map(|error_type| error_type.trim().parse::<i32>().ok().filter(|e| (0..=2).contains(e)))
+            .collect();
+        match error_types {
+            Some(mut error_types) => {
+                error_types.sort();
+                error_types.dedup();
+                return error_types;
+            },
+            None => {
+                println!("Invalid error types, insert another list");
+            }
+        }
+    }
+}
+/// Legge da stdin una percentuale strettamente compresa tra 0 e 100 e la ritorna come frazione
+fn read_percentage(prompt: &str) -> f64 {
+    println!("{}", prompt);
+    loop {
+        let mut input = String::new();
+        println!("> ");
+        std::io::stdin().read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim().parse::<f64>() {
+            Ok(number) if number > 0.0 && number < 100.0 => return number / 100.0,
+            Ok(_) => {
+                println!("The percentage must be between 0 and 100, try another one!");
+            },
+            Err(_) => {
+                println!("Failed to convert number, insert another value");
+            }
+        }
+    }
+}
 pub fn print_n_fault_menu(n_fault: &mut i32){
 
     println!("Insert number to select the number of faults!");
@@ -121,7 +265,7 @@ pub fn print_n_fault_menu(n_fault: &mut i32){
 
         match trimmed_input.parse::<i32>() {
             Ok(number) => {
-                if number<0{
+                if number<=0{
                     println!("Invalid number, try another one!");
                 }else{
                     *n_fault = number;
@@ -135,7 +279,37 @@ pub fn print_n_fault_menu(n_fault: &mut i32){
     }
 
 }
-pub fn print_configuration(components: &Vec<i32>, error_index:  i32, n_faults: i32){
+/// Chiede il seme della campagna (vuoto per un seme casuale) e, se specificato, l'indice
+/// dell'unica inferenza da riprodurre (vuoto per eseguire tutta la campagna)
+pub fn print_seed_menu(seed: &mut Option<u64>, replay: &mut Option<usize>){
+    println!("Insert the campaign seed! - (empty for a random seed)");
+    *seed = read_optional::<u64>();
+    if seed.is_some() {
+        println!("Insert the index of the run to replay! - (empty to run the whole campaign)");
+        *replay = read_optional::<usize>();
+    }
+}
+/// Legge da stdin un valore opzionale: una riga vuota corrisponde a `None`
+fn read_optional<T: std::str::FromStr>() -> Option<T> {
+    loop {
+        let mut input = String::new();
+        println!("> ");
+        std::io::stdin().read_line(&mut input)
+            .expect("Failed to read line");
+
+        let trimmed_input = input.trim();
+        if trimmed_input.is_empty() {
+            return None;
+        }
+        match trimmed_input.parse::<T>() {
+            Ok(value) => return Some(value),
+            Err(_) => {
+                println!("Failed to convert number, insert another value");
+            }
+        }
+    }
+}
+pub fn print_configuration(components: &[i32], error_types: &[i32], n_faults: i32, mode: &CampaignMode, seed: u64, replay: Option<usize>){
     let mut components_string = String::from("                                 #");
     for i in components{
         match i {
@@ -147,15 +321,25 @@ pub fn print_configuration(components: &Vec<i32>, error_index:  i32, n_faults: i
             5 => components_string += "\n#             -Adder Input                            #",
             6 => components_string += "\n#             -Multiplier Output                      #",
             7 => components_string += "\n#             -Multiplier Input                       #",
+            8 => components_string += "\n#             -Refractory Counter                     #",
+            9 => components_string += "\n#             -Auxiliary Variable                     #",
+            10 => components_string += "\n#             -Exp Unit Output                        #",
+            11 => components_string += "\n#             -Exp Unit Input                         #",
+            12 => components_string += "\n#             -Comparator Output                      #",
             _ => components_string += "\n#             -None                                   #",
         }
     }
     let mut error_type = String::from("                                 #");
-    match error_index{
-        0=> error_type += "\n#             Stuck-At-0                              #",
-        1=> error_type += "\n#             Stuck-At-1                              #",
-        2=> error_type += "\n#             Flip-bit                                #",
-        _ => error_type += "\n#            None                                     #",
+    for i in error_types {
+        match i {
+            0 => error_type += "\n#             Stuck-At-0                              #",
+            1 => error_type += "\n#             Stuck-At-1                              #",
+            2 => error_type += "\n#             Flip-bit                                #",
+            _ => {},
+        }
+    }
+    if error_types.is_empty() {
+        error_type += "\n#            None                                     #";
     }
     println!("#######################################################");
     println!("#                                                     #");
@@ -165,12 +349,24 @@ pub fn print_configuration(components: &Vec<i32>, error_index:  i32, n_faults: i
     println!("#                                                     #");
     println!("#         Error Type:{}                               ", error_type);
     println!("#                                                     #");
+    println!("#         Campaign:                                   #");
+    println!("#             {}                                      ", mode);
+    println!("#                                                     #");
     println!("#         Number of Faults:                           #");
     println!("#             {}                                      #", n_faults);
     println!("#                                                     #");
+    println!("#         Seed:                                       #");
+    println!("#             {}                                      ", seed);
+    if let Some(run) = replay {
+        println!("#                                                     #");
+        println!("#         Replayed Run:                               #");
+        println!("#             {}                                      ", run);
+    }
+    println!("#                                                     #");
     println!("#######################################################");
 }
-pub fn write_configuration_to_file(file: &mut File, components: &Vec<i32>, error_index: i32, n_faults: i32) -> Result<(), Error> {
+pub fn write_configuration_to_file(file: &mut File, components: &[i32], error_types: &[i32], n_faults: i32, mode: &CampaignMode,
+                                   seed: u64, replay: Option<usize>) -> Result<(), Error> {
     let mut components_string = String::from("                                 #");
     for i in components {
         match i {
@@ -182,15 +378,25 @@ pub fn write_configuration_to_file(file: &mut File, components: &Vec<i32>, error
             5 => components_string += "\n#             -Adder Input                            #",
             6 => components_string += "\n#             -Multiplier Output                      #",
             7 => components_string += "\n#             -Multiplier Input                       #",
+            8 => components_string += "\n#             -Refractory Counter                     #",
+            9 => components_string += "\n#             -Auxiliary Variable                     #",
+            10 => components_string += "\n#             -Exp Unit Output                        #",
+            11 => components_string += "\n#             -Exp Unit Input                         #",
+            12 => components_string += "\n#             -Comparator Output                      #",
             _ => components_string += "\n#             -None                                   #",
         }
     }
     let mut error_type = String::from("                                 #");
-    match error_index {
-        0 => error_type += "\n#             Stuck-At-0                              #",
-        1 => error_type += "\n#             Stuck-At-1                              #",
-        2 => error_type += "\n#             Flip-bit                                #",
-        _ => error_type += "\n#            None                                     #",
+    for i in error_types {
+        match i {
+            0 => error_type += "\n#             Stuck-At-0                              #",
+            1 => error_type += "\n#             Stuck-At-1                              #",
+            2 => error_type += "\n#             Flip-bit                                #",
+            _ => {},
+        }
+    }
+    if error_types.is_empty() {
+        error_type += "\n#            None                                     #";
     }
 
 
@@ -204,9 +410,20 @@ pub fn write_configuration_to_file(file: &mut File, components: &Vec<i32>, error
     writeln!(file, "#                                                     #")?;
     writeln!(file, "#         Error Type:{}                               ", error_type)?;
     writeln!(file, "#                                                     #")?;
+    writeln!(file, "#         Campaign:                                   #")?;
+    writeln!(file, "#             {}                                      ", mode)?;
+    writeln!(file, "#                                                     #")?;
     writeln!(file, "#         Number of Faults:                           #")?;
     writeln!(file, "#             {}                                      #", n_faults)?;
     writeln!(file, "#                                                     #")?;
+    writeln!(file, "#         Seed:                                       #")?;
+    writeln!(file, "#             {}                                      ", seed)?;
+    if let Some(run) = replay {
+        writeln!(file, "#                                                     #")?;
+        writeln!(file, "#         Replayed Run:                               #")?;
+        writeln!(file, "#             {}                                      ", run)?;
+    }
+    writeln!(file, "#                                                     #")?;
     writeln!(file, "#######################################################")?;
 
 
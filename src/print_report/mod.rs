@@ -1,2 +1,3 @@
 pub mod info_table;
-pub mod menu_handler;
\ No newline at end of file
+pub mod menu_handler;
+pub mod cli;
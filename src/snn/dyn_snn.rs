@@ -0,0 +1,284 @@
+use std::fmt::{Display, Formatter};
+use std::slice::IterMut;
+use std::sync::{Arc, Mutex};
+use crate::snn::layer::Layer;
+use crate::snn::neuron::Neuron;
+use crate::snn::Evento;
+use crate::snn::processor::Processor;
+use rand::Rng;
+use rand::rngs::StdRng;
+use crate::error_handling::components::FaultyUnits;
+
+/// Informazioni di un errore transitorio o intermittente in attesa di essere iniettato da `process()`
+#[derive(Debug, Clone, Copy)]
+pub struct TransientErrorInfo {
+    /// layer del neurone scelto
+    pub layer: usize,
+    /// neurone scelto
+    pub neuron: usize,
+    /// unità di blocchi elaborativi affetta, se l'errore riguarda un blocco elaborativo
+    pub unit: Option<usize>,
+    /// componente affetto da errore
+    pub component: i32,
+    /// indice del peso affetto (neurone sorgente), solo per gli errori sui pesi
+    pub weight: Option<usize>,
+    /// tipo di errore applicato negli istanti in cui l'errore è attivo
+    pub error_type: i32,
+    /// maschera dei bit affetti da errore
+    pub mask: u64,
+    /// errori sugli ingressi dei blocchi elaborativi
+    pub input_errors: (i32, i32),
+    /// numero di istanti consecutivi in cui un blocco elaborativo resta affetto da un errore transitorio
+    pub duration: u64,
+    /// probabilità di attivazione in ciascun istante di un errore intermittente; `None` per un errore transitorio
+    pub probability: Option<f64>,
+    /// istante iniziale prefissato di un errore transitorio; `None` se scelto casualmente da `process()`
+    pub instant: Option<u64>,
+}
+
+/// Errori negli impulsi in ingresso passati a `DynSNN::process()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputError {
+    /// Il vettore di impulsi dell'istante `instant` non ha la dimensione dell'input della rete
+    WrongInputDim { instant: usize, expected: usize, found: usize },
+    /// Il vettore di impulsi dell'istante `instant` contiene un valore diverso da `0` e `1`
+    InvalidSpike { instant: usize, value: u8 },
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::WrongInputDim { instant, expected, found } =>
+                write!(f, "input at t={} has {} spikes, expected {}", instant, found, expected),
+            InputError::InvalidSpike { instant, value } =>
+                write!(f, "input at t={} contains the value {}, spikes must be 0 or 1", instant, value),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Struttura che rappresenta la rete neurale con dimensioni di input e output note solo a *runtime*
+/// (e.g. rete caricata da file). Le dimensioni vengono controllate da `SnnBuilder::build_dyn()`
+/// # Campi
+/// * `layers` - vettore dei layer che costituiscono la rete
+/// * `input_dim` - dimensione dell'input della rete
+/// * `output_dim` - dimensione dell'output della rete
+/// * `transient_errors` - un elemento per ciascun errore iniettato nella rete: contiene le informazioni di un
+///   errore transitorio o intermittente, in attesa che vengano selezionati gli istanti in cui è attivo, oppure `None`
+///   per un errore permanente (già iniettato alla costruzione)
+/// * `transient_windows` - per ciascun errore, istante iniziale e durata dell'errore transitorio iniettato nell'ultima elaborazione
+/// * `fires` - per ciascun errore, numero di istanti in cui l'errore transitorio o intermittente è stato effettivamente applicato nell'ultima elaborazione
+/// * `faulty_units` - blocchi elaborativi affetti da errori permanenti, indicizzati dall'unità a cui appartengono
+/// * `values` - per ciascun errore, valore originale e valore corrotto del registro colpito: noti alla costruzione
+///   per gli errori permanenti su soglia e pesi, registrati alla prima attivazione per gli errori transitori e intermittenti
+///   sui registri (`None` per i blocchi elaborativi e gli errori permanenti sui registri di stato)
+/// * `rng` - generatore di numeri casuali dell'inferenza, lo stesso usato dal builder per scegliere gli errori:
+///   a partire dallo stesso seme vengono scelti gli stessi istanti di attivazione degli errori
+/// # Tipi
+/// * `N` - tipo generico per rappresentare un Neurone
+pub struct DynSNN<N: Neuron + Clone+'static>{
+    layers: Vec<Arc<Mutex<Layer<N>>>>,
+    input_dim: usize,
+    output_dim: usize,
+    transient_errors: Vec<Option<TransientErrorInfo>>,
+    transient_windows: Vec<Option<(u64, u64)>>,
+    fires: Vec<Option<u64>>,
+    faulty_units: FaultyUnits,
+    values: Vec<Option<(f64, f64)>>,
+    rng: StdRng
+}
+
+impl <N: Neuron + Clone+'static> DynSNN<N> {
+    pub fn new(layers: Vec<Arc<Mutex<Layer<N>>>>, input_dim: usize, output_dim: usize,
+               transient_errors: Vec<Option<TransientErrorInfo>>, values: Vec<Option<(f64, f64)>>, faulty_units: FaultyUnits, rng: StdRng) -> Self {
+        let n_errors = transient_errors.len();
+        Self {
+            layers,
+            input_dim,
+            output_dim,
+            transient_errors,
+            transient_windows: vec![None; n_errors],
+            fires: vec![None; n_errors],
+            faulty_units,
+            values,
+            rng,
+        }
+    }
+
+    pub fn layers(&self) -> &Vec<Arc<Mutex<Layer<N>>>> {
+        &self.layers
+    }
+    pub fn input_dim(&self) -> usize {
+        self.input_dim
+    }
+    pub fn output_dim(&self) -> usize {
+        self.output_dim
+    }
+    pub fn faulty_units(&self) -> &FaultyUnits {
+        &self.faulty_units
+    }
+/// Ritorna, per ciascun errore iniettato nella rete, l'istante iniziale e la durata dell'errore transitorio
+/// nell'ultima chiamata a `process()` (`None` per gli errori permanenti e intermittenti)
+    pub fn transient_windows(&self) -> &[Option<(u64, u64)>] {
+        &self.transient_windows
+    }
+/// Ritorna, per ciascun errore iniettato nella rete, il numero di istanti in cui l'errore transitorio o intermittente
+/// è stato effettivamente applicato nell'ultima chiamata a `process()`, i.e. era attivo e la rete ha processato un input
+/// (`0` se l'errore non ha mai avuto effetto), `None` per gli errori permanenti
+    pub fn fires(&self) -> &[Option<u64>] {
+        &self.fires
+    }
+/// Ritorna, per ciascun errore iniettato nella rete, il valore originale e il valore corrotto del registro colpito
+/// (per gli errori transitori e intermittenti, alla prima attivazione nell'ultima chiamata a `process()`)
+    pub fn values(&self) -> &[Option<(f64, f64)>] {
+        &self.values
+    }
+
+/// Funzione per processare gli impulsi in ingresso. Ritorna gli impulsi in uscita dalla rete,
+/// un vettore di dimensione `output_dim` per ciascun istante di tempo.
+/// Per ciascun errore transitorio in `transient_errors` viene selezionato
+/// un istante casuale dipendente dalla durata dell'input in cui si presenterà l'errore;
+/// un errore su un blocco elaborativo resta attivo per la durata specificata, a partire da tale istante,
+/// mentre un errore su un registro viene iniettato nel primo istante, a partire da quello scelto, in cui il layer processa un input.
+/// Un errore intermittente si attiva invece in ciascun istante con la probabilità specificata.
+/// # Argomenti
+/// * `input_spikes` - vettore di vettori di impulsi in istanti successivi; ciascun vettore deve avere dimensione `input_dim`
+/// # Errori
+/// Ritorna un `InputError`, senza processare l'input, se un vettore di impulsi non ha dimensione `input_dim`
+/// o contiene valori diversi da `0` e `1`
+    pub fn process(&mut self, input_spikes: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, InputError> {
+        /* trasformiamo l'input in Eventi */
+        let input_events = self.spikes_to_events(input_spikes)?;
+        self.transient_windows.iter_mut().for_each(|window| *window = None);
+        self.fires.iter_mut().for_each(|fires| *fires = None);
+        self.layers.iter().for_each(|l| l.lock().unwrap().clear_transient_errors());
+        if !input_spikes.is_empty() {
+            let rng = &mut self.rng;
+            let duration = input_spikes.len() as u64;
+            for (index, error) in self.transient_errors.iter().enumerate() {
+                let Some(error) = error else { continue; };
+                /* istanti in cui l'errore è attivo, scelti una volta sola per tutti i layer */
+                let instants: Vec<u64> = match error.probability {
+                    Some(probability) => (0..duration).filter(|_| rng.gen_bool(probability)).collect(),
+                    None => {
+                        let random_instant:u64=error.instant.unwrap_or_else(|| rng.gen_range(0..duration));
+                        /* il valore di un registro viene modificato una sola volta */
+                        let length = if error.unit.is_some() { error.duration } else { 1 };
+                        self.transient_windows[index] = Some((random_instant, length));
+                        (random_instant..random_instant + length).collect()
+                    }
+                };
+                if error.unit.is_some() {
+                    /* errore su un blocco elaborativo: l'unità affetta può essere utilizzata dai neuroni di
+                        qualsiasi layer, che useranno il blocco con errore solo se assegnati a tale unità */
+                    for l in self.layers.iter() {
+                        l.lock().unwrap().add_transient_error(error, index, instants.clone());
+                    }
+                } else {
+                    /* settiamo l'errore sul layer corrispontente */
+                    self.layers[error.layer].lock().unwrap().add_transient_error(error, index, instants);
+                }
+            }
+        }
+        let processor = Processor {};
+        let faulty_units = self.faulty_units.clone();
+        let output_events = processor.process_events(&mut *self, input_events, faulty_units);
+        /* istanti in cui gli errori transitori e intermittenti sono stati effettivamente applicati
+            (un errore su un blocco elaborativo può essere applicato da più layer nello stesso istante)
+            e valori originale e corrotto dei registri colpiti */
+        for (index, error) in self.transient_errors.iter().enumerate() {
+            let Some(error) = error else { continue; };
+            let mut activations: Vec<u64> = match error.unit {
+                Some(_) => self.layers.iter().flat_map(|l| l.lock().unwrap().transient_activations(index).to_vec()).collect(),
+                None => self.layers[error.layer].lock().unwrap().transient_activations(index).to_vec(),
+            };
+            activations.sort();
+            activations.dedup();
+            /* un errore transitorio su un registro viene iniettato nel primo istante processato dal layer */
+            if let (None, None, Some(&first)) = (error.unit, error.probability, activations.first()) {
+                self.transient_windows[index] = Some((first, 1));
+            }
+            self.fires[index] = Some(activations.len() as u64);
+            self.values[index] = self.layers[error.layer].lock().unwrap().transient_values(index);
+        }
+
+        /* trasformiamo gli Eventi di output in vettori di segnali, in modo tale che
+            il valore di ritorno sia coerente con l'argomento in ingresso della funzione */
+        Ok(self.spikes_from_events(output_events, input_spikes.len()))
+    }
+
+/// Trasforma i vettori di segnali in ingresso in Eventi di impulsi che contegano le stesse informazioni.
+/// Controlla inoltre che i valori passati rappresentino effettivamente dei segnali, i.e. siano `0` o `1`,
+/// e che ciascun vettore abbia la dimensione dell'input della rete
+    fn spikes_to_events(&self, spikes_matrix: &[Vec<u8>]) -> Result<Vec<Evento>, InputError> {
+        let mut eventi = Vec::<Evento>::new();
+        for (ts, spikes) in spikes_matrix.iter().enumerate() {
+            if spikes.len() != self.input_dim {
+                return Err(InputError::WrongInputDim { instant: ts, expected: self.input_dim, found: spikes.len() });
+            }
+            if let Some(&value) = spikes.iter().find(|&&s| s!=0 && s!=1){
+                return Err(InputError::InvalidSpike { instant: ts, value });
+            }
+            let evento_ts=Evento::new(ts as u64, spikes.clone());
+            eventi.push(evento_ts);
+        }
+
+        Ok(eventi)
+    }
+
+/// Trasforma gli Eventi in vettori di segnali; gli istanti senza alcun Evento in uscita valgono `0`
+    fn spikes_from_events(&self, eventi: Vec<Evento>, spikes_duration: usize) -> Vec<Vec<u8>> {
+        let mut raw_matrix = vec![vec![0u8; self.output_dim]; spikes_duration];
+
+        for evento in eventi {
+            if evento.spikes.len() != self.output_dim{
+                panic!("Error: spikes in the event should equal the output dimension")
+            }
+            for (n_index, spike) in evento.spikes.into_iter().enumerate() {
+                raw_matrix[evento.ts as usize][n_index] = spike;
+            }
+        }
+
+        raw_matrix
+    }
+}
+
+impl<'a, N: Neuron+Clone+'static> IntoIterator for &'a mut DynSNN<N>{
+    type Item = &'a mut Arc<Mutex<Layer<N>>>;
+    type IntoIter = IterMut<'a, Arc<Mutex<Layer<N>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.layers.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::lifneuron::LIFNeuron;
+    use crate::print_report::info_table::InfoTable;
+    use crate::snn::snn_builder::SnnBuilder;
+
+    fn network() -> DynSNN<LIFNeuron> {
+        let mut builder = SnnBuilder::new();
+        builder.add_layer().add_weight([[0.5, 0.5], [0.5, 0.5]]).unwrap()
+            .add_neurons([LIFNeuron::new(0.3, 0.0, 0.0, 1.0, 1.0), LIFNeuron::new(0.3, 0.0, 0.0, 1.0, 1.0)]).unwrap()
+            .add_intra_weights([[0.0, -0.1], [-0.1, 0.0]]).unwrap();
+        builder.build_dyn(&[], -1, &mut InfoTable::new()).unwrap()
+    }
+
+    #[test]
+    fn valid_input() {
+        let mut snn = network();
+        assert_eq!(snn.process(&[vec![1, 0], vec![0, 0]]), Ok(vec![vec![1, 1], vec![0, 0]]));
+        assert_eq!(snn.process(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut snn = network();
+        assert_eq!(snn.process(&[vec![1, 0], vec![1, 0, 1]]), Err(InputError::WrongInputDim { instant: 1, expected: 2, found: 3 }));
+        assert_eq!(snn.process(&[vec![0, 2]]), Err(InputError::InvalidSpike { instant: 0, value: 2 }));
+    }
+}
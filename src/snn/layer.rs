@@ -1,26 +1,57 @@
 use std::sync::mpsc::{Receiver, Sender};
-use crate::snn::Evento;
+use crate::snn::{AccumulationMode, Evento};
 use crate::snn::neuron::Neuron;
 use crate::error_handling::error_handling;
-use rand::Rng;
-use crate::error_handling::components::{Adder, Multiplier};
+use crate::error_handling::components::{ArithmeticUnits, FaultyUnits};
+use crate::error_handling::numeric_format::NumericFormat;
+use crate::snn::dyn_snn::TransientErrorInfo;
 
-/// Struttura che rappresenta un errore transitorio bit-flip su un bit di un componente
+/// Struttura che rappresenta un errore transitorio o intermittente su uno o più bit di un componente,
+/// attivo solo in alcuni istanti di tempo
 struct TransientError{
+/// Indice dell'errore nell'insieme di errori iniettati nella rete
+    fault: usize,
 /// Indice del neurone su cui è presente l'errore
     neuron:usize,
+/// Unità di blocchi elaborativi affetta, se l'errore riguarda un blocco elaborativo
+    unit: Option<usize>,
 /// Componente su cui è presente l'errore
     component:i32,
-/// Posizione del bit del componente su cui effettuare il flip
-    position:u8,
-/// Istante di tempo in cui si verifica l'errore
-    time: u64,
+/// Indice del peso affetto (neurone sorgente), per gli errori sui pesi
+    weight: Option<usize>,
+/// Tipo di errore applicato negli istanti in cui l'errore è attivo
+    error_type: i32,
+/// Maschera dei bit del componente affetti dall'errore
+    mask:u64,
+/// Istanti di tempo, in ordine crescente, in cui l'errore è attivo
+    instants: Vec<u64>,
 
-    input_errors: (i32,i32)
+    input_errors: (i32,i32),
+/// Valore originale e valore corrotto del registro alla prima attivazione dell'errore
+/// (`None` per i blocchi elaborativi o se l'errore non si è ancora attivato)
+    values: Option<(f64, f64)>,
+/// Istanti in cui l'errore è stato effettivamente applicato, i.e. attivi e in cui il layer ha processato un input
+    activations: Vec<u64>,
+/// `true` se l'errore è intermittente, i.e. presente solo negli istanti in cui è attivo
+    intermittent: bool,
+/// Valore corretto del parametro costante (soglia o peso) colpito da un errore intermittente,
+/// ripristinato negli istanti in cui l'errore non è attivo (`None` prima della prima attivazione)
+    original: Option<f64>
 }
 impl TransientError {
-    pub fn new(neuron: usize, component: i32, position: u8, time: u64, input_errors: (i32, i32)) -> Self {
-        Self { neuron, component, position, time, input_errors }
+    pub fn new(info: &TransientErrorInfo, fault: usize, instants: Vec<u64>) -> Self {
+        Self { fault, neuron: info.neuron, unit: info.unit, component: info.component, weight: info.weight, error_type: info.error_type,
+            mask: info.mask, instants, input_errors: info.input_errors, values: None, activations: vec![], intermittent: info.probability.is_some(),
+            original: None }
+    }
+/// Ritorna `true` se l'errore deve essere applicato nell'istante `instant`, in cui il layer processa un input.
+/// Un errore transitorio su un registro viene applicato una sola volta, nel primo istante processato
+/// a partire da quello scelto: il valore del registro resta corrotto finché non viene letto
+    fn is_active(&self, instant: u64) -> bool {
+        match !self.intermittent && self.unit.is_none() {
+            true => self.activations.is_empty() && self.instants.first().is_some_and(|&start| instant >= start),
+            false => self.instants.binary_search(&instant).is_ok(),
+        }
     }
 }
 /// Layer della rete neurale
@@ -34,8 +65,14 @@ pub struct Layer<N: Neuron+Clone+'static>{
     intra_weights: Vec<Vec<f64>>,
 /// Impulsi di output del layer nell'istante precendete
     prev_output: Vec<u8>,
-/// Eventuale errore transitorio su uno dei componenti del layer
-    error: Option<TransientError>
+/// Accumulazioni delle somme pesate che passano dal sommatore
+    accumulation: AccumulationMode,
+/// Indice dell'unità di blocchi elaborativi assegnata a ciascun neurone del layer
+    units: Vec<usize>,
+/// Formato numerico del datapath del layer (stato dei neuroni, pesi e blocchi elaborativi)
+    format: NumericFormat,
+/// Errori transitori o intermittenti sui componenti del layer
+    errors: Vec<TransientError>
 }
 
 impl<N: Neuron+ Clone+'static> Layer<N> {
@@ -52,148 +89,216 @@ impl<N: Neuron+ Clone+'static> Layer<N> {
     pub fn prev_output(&self) -> &Vec<u8> {
         &self.prev_output
     }
+    pub fn accumulation(&self) -> AccumulationMode {
+        self.accumulation
+    }
+    pub fn units(&self) -> &Vec<usize> {
+        &self.units
+    }
+    pub fn format(&self) -> NumericFormat {
+        self.format
+    }
 /// Ritorna un nuovo layer per una rete neurale
 /// # Argomenti
 /// * `neurons` - vettore di neuroni che costituiscono il layer
 /// * `weights` - pesi con il layer precedente
 /// * `intra_weights` - pesi interni tra i neuroni del layer stesso
+/// * `accumulation` - accumulazioni delle somme pesate che passano dal sommatore
+/// * `units` - indice dell'unità di blocchi elaborativi assegnata a ciascun neurone
+/// * `format` - formato numerico del datapath, determina i bit su cui agiscono gli errori
 /// # Valori predefiniti
 /// * `prev_output` - output precedente del layer settato con valori a 0
-/// * `error` - nessun errore transitorio (Option::None)
-    pub fn new(neurons: Vec<N>, weights: Vec<Vec<f64>>, intra_weights: Vec<Vec<f64>>)->Self{
+/// * `errors` - nessun errore transitorio
+    pub fn new(neurons: Vec<N>, weights: Vec<Vec<f64>>, intra_weights: Vec<Vec<f64>>, accumulation: AccumulationMode, units: Vec<usize>, format: NumericFormat)->Self{
         let len= neurons.len();
         Self{
             neurons,
             weights,
             intra_weights,
             prev_output: vec![0; len],
-            error:None
+            accumulation,
+            units,
+            format,
+            errors: vec![]
         }
     }
-/// Setta un errore transitorio bit-flip su uno dei componenti del layer
+/// Aggiunge un errore transitorio o intermittente su uno dei componenti del layer
 /// # Argomenti
-/// * `neuron` - indice del neurone sul cui relativo componente è presente l'errore
-/// * `component` - componente su cui avviene l'errore. Può avere valore:
-///     * `0` -> potenziale di soglia
-///     * `1` -> potenziale di membrana
-///     * `2` -> uno dei pesi esterni, verso il neurone specificato
-///     * `3` -> uno dei pesi interni, dal neurone specificato
-///     * `4` -> uscita del sommatore
-///     * `5` -> ingresso del sommatore
-///     * `6` -> uscita del moltiplicatore
-///     * `7` -> ingresso del moltiplicatore
-/// * `position` - posizione del bit affetto da errore
-/// * `time` - istante di tempo in cui si verifica l'errore
-/// * `input_errors` - nei casi `component=5` o `7`, specifica se su quale ingresso c'è l'errore
-    pub fn set_transient_error(&mut self, neuron: usize, component: i32, position: u8, time: u64, input_errors: (i32, i32)){
-        self.error=Some(TransientError::new(neuron,component,position,time, input_errors))
-    }
-/// Genera a caso l'indice del peso su cui applicare l'errore
-    fn random_w_index(w:&Vec<f64>)->usize{
-        let mut rng = rand::thread_rng();
-        rng.gen_range(0..w.len())
-    }
-/// Funzione per controllare la presenza di un errore transitorio nel layer
-/// e se questo avviene nell'istante *current_instant* specificato.
-/// Nei casi di errore su sommatore o moltiplicatore, ritorna un Option con i componenti modificati, negli altri casi None
-    fn check_transient_error(&mut self, current_instant: u64, adder: &mut Adder,  mult: &mut Multiplier) ->Option<(Adder, Multiplier)>{
-        if self.error.is_none() { return None; }
-        let transient_error= self.error.as_ref().unwrap();
-        /* controllo sull'istante di tempo*/
-        if transient_error.time !=current_instant { return None; }
-
-        let n=&mut self.neurons[transient_error.neuron];
-        let position=transient_error.position;
-        match transient_error.component {
-            //Threshold
-            0=>{error_handling::threshold_fault(n,2,position); None},
-            //Membrane
-            1=>{error_handling::membrane_fault(n,2,position); None},
-            //Extra
-            2=>{
-                let w= &mut self.weights[transient_error.neuron];
-                let index=Layer::<N>::random_w_index(w);
-                error_handling::weight_fault(&mut w[index],2, position);
-                None
-            },
-            //Intra
-            3=>{
-                let w= &mut self.intra_weights[transient_error.neuron];
-                let index=Layer::<N>::random_w_index(w);
-                error_handling::weight_fault(&mut w[index],2, position);
-                None
-            },
-            // Adder output
-            4=>{
-                adder.set_params(2, position);
-                Some((*adder, *mult))
-            },
-            // Adder inputs
-            5=>{
-                adder.set_params_input(position, transient_error.input_errors.0,transient_error.input_errors.1);
-                Some((*adder, *mult))
-            },
-            // Multiplier output
-            6=>{
-                mult.set_params(2, position);
-                Some((*adder, *mult))
-            },
-            // Multiplier input
-            7=>{
-                mult.set_params_input(position, transient_error.input_errors.0,transient_error.input_errors.1);
-                Some((*adder, *mult))
-            },
-            _=>{None},
+/// * `error` - informazioni dell'errore: neurone, eventuale unità di blocchi elaborativi, componente
+///   (stessi indici di `SnnBuilder::build()`), peso affetto, tipo di errore, maschera dei bit ed errori sugli ingressi
+/// * `fault` - indice dell'errore nell'insieme di errori iniettati nella rete
+/// * `instants` - istanti di tempo, in ordine crescente, in cui l'errore è attivo: in ciascuno di essi un blocco elaborativo
+///   è affetto dall'errore, mentre il valore di un registro (soglia, membrana, pesi, ...) viene modificato una volta
+    pub fn add_transient_error(&mut self, error: &TransientErrorInfo, fault: usize, instants: Vec<u64>){
+        self.errors.push(TransientError::new(error, fault, instants))
+    }
+/// Ritorna gli istanti in cui l'errore `fault` è stato effettivamente applicato dal layer: un errore attivo
+/// in un istante in cui il layer non riceve alcun input non viene applicato
+    pub fn transient_activations(&self, fault: usize) -> &[u64] {
+        self.errors.iter().find(|e| e.fault == fault).map(|e| e.activations.as_slice()).unwrap_or(&[])
+    }
+/// Ritorna il valore originale e il valore corrotto del registro colpito dall'errore `fault` alla sua prima attivazione,
+/// `None` se l'errore riguarda un blocco elaborativo o non si è attivato
+    pub fn transient_values(&self, fault: usize) -> Option<(f64, f64)> {
+        self.errors.iter().find(|e| e.fault == fault).and_then(|e| e.values)
+    }
+/// Ritorna il valore corrente del registro `component` del neurone `neuron` (per i pesi, del peso `weight`),
+/// `None` per i componenti che non sono registri del layer
+    fn register_value(&self, neuron: usize, component: i32, weight: Option<usize>) -> Option<f64> {
+        match (component, weight) {
+            (0, _) => Some(self.neurons[neuron].get_th()),
+            (1, _) => Some(self.neurons[neuron].get_mem()),
+            (2, Some(index)) => Some(self.weights[neuron][index]),
+            (3, Some(index)) => Some(self.intra_weights[neuron][index]),
+            (8, _) => Some(self.neurons[neuron].get_refractory() as f64),
+            (9, _) => Some(self.neurons[neuron].get_aux()),
+            _ => None,
+        }
+    }
+/// Setta il valore del parametro costante `component` (soglia o peso `weight`) del neurone `neuron`
+    fn set_parameter(&mut self, neuron: usize, component: i32, weight: Option<usize>, value: f64) {
+        match (component, weight) {
+            (0, _) => self.neurons[neuron].set_th(value),
+            (2, Some(index)) => self.weights[neuron][index] = value,
+            (3, Some(index)) => self.intra_weights[neuron][index] = value,
+            _ => {},
+        }
+    }
+/// Rimuove tutti gli errori transitori o intermittenti del layer, ripristinando i parametri costanti
+/// colpiti da errori intermittenti
+    pub fn clear_transient_errors(&mut self){
+        for transient_error in std::mem::take(&mut self.errors) {
+            if let Some(original) = transient_error.original {
+                self.set_parameter(transient_error.neuron, transient_error.component, transient_error.weight, original);
+            }
         }
     }
+/// Funzione per controllare la presenza di errori transitori o intermittenti nel layer
+/// attivi nell'istante *current_instant* specificato.
+/// Gli errori sui registri dei neuroni vengono iniettati direttamente, mentre quelli su sommatore, moltiplicatore,
+/// unità esponenziale o comparatore vengono inseriti in `units`, copia dei blocchi elaborativi
+/// affetti da errore valida solo per questo istante di tempo
+    fn check_transient_error(&mut self, current_instant: u64, units: &mut FaultyUnits){
+        let mut errors = std::mem::take(&mut self.errors);
+        /* un errore intermittente su un parametro costante è presente solo negli istanti in cui è attivo:
+            il valore corretto viene ripristinato prima di controllare quali errori sono attivi in questo istante */
+        for transient_error in errors.iter().filter(|e| e.intermittent) {
+            if let Some(original) = transient_error.original {
+                self.set_parameter(transient_error.neuron, transient_error.component, transient_error.weight, original);
+            }
+        }
+        /* controllo sull'istante di tempo */
+        for transient_error in errors.iter_mut().filter(|e| e.is_active(current_instant)) {
+            transient_error.activations.push(current_instant);
+            let neuron=transient_error.neuron;
+            let mask=transient_error.mask;
+            let error_type=transient_error.error_type;
+            if let Some(unit) = transient_error.unit {
+                /* blocchi elaborativi dell'unità affetta, senza errori se l'unità non ne ha di permanenti */
+                let unit = units.entry(unit).or_insert_with(|| ArithmeticUnits::with_format(self.format));
+                match transient_error.component {
+                    // Adder output
+                    4=>{unit.adder.set_params(error_type, mask);},
+                    // Adder inputs
+                    5=>{unit.adder.set_params_input(mask, transient_error.input_errors.0,transient_error.input_errors.1);},
+                    // Multiplier output
+                    6=>{unit.mult.set_params(error_type, mask);},
+                    // Multiplier input
+                    7=>{unit.mult.set_params_input(mask, transient_error.input_errors.0,transient_error.input_errors.1);},
+                    // Exp unit output
+                    10=>{unit.exp_unit.set_params(error_type, mask);},
+                    // Exp unit input
+                    11=>{unit.exp_unit.set_params_input(mask, transient_error.input_errors.0);},
+                    // Comparator output
+                    12=>{unit.cmp.set_params(error_type);},
+                    _=>{},
+                }
+                continue;
+            }
+            let original = self.register_value(neuron, transient_error.component, transient_error.weight);
+            if transient_error.intermittent && matches!(transient_error.component, 0 | 2 | 3) && transient_error.original.is_none() {
+                transient_error.original = original;
+            }
+            match transient_error.component {
+                //Threshold
+                0=>{error_handling::threshold_fault(&mut self.neurons[neuron],error_type,mask,self.format);},
+                //Membrane
+                1=>{error_handling::membrane_upset(&mut self.neurons[neuron],error_type,mask,self.format);},
+                //Extra
+                2=>{
+                    let index = transient_error.weight.expect("Weight faults always target a weight index");
+                    error_handling::weight_fault(&mut self.weights[neuron][index],error_type, mask, self.format);
+                },
+                //Intra
+                3=>{
+                    let index = transient_error.weight.expect("Weight faults always target a weight index");
+                    error_handling::weight_fault(&mut self.intra_weights[neuron][index],error_type, mask, self.format);
+                },
+                // Refractory counter
+                8=>{error_handling::refractory_upset(&mut self.neurons[neuron],error_type,mask);},
+                // Auxiliary state variable
+                9=>{error_handling::aux_upset(&mut self.neurons[neuron],error_type,mask,self.format);},
+                _=>{},
+            }
+            /* i valori vengono registrati alla prima attivazione dell'errore */
+            if transient_error.values.is_none() {
+                let corrupted = self.register_value(neuron, transient_error.component, transient_error.weight);
+                transient_error.values = original.zip(corrupted);
+            }
+        }
+        self.errors = errors;
+    }
 /// Funzione per processare gli impulsi in input al layer
 /// # Argomenti
-/// * `adder` - Componente Sommatore utilizzabile dai neuroni
-/// * `multiplier` - Componente Moltiplicatore utilizzabile dai neuroni
+/// * `faulty_units` - blocchi elaborativi affetti da errori permanenti, indicizzati dall'unità a cui appartengono;
+///   i neuroni assegnati alle altre unità utilizzano blocchi elaborativi senza errori
 /// * `layer_input_rc` - **Receiver** del channel con il layer precedente, attende la ricezione dell'Evento rappresentante gli impulsi in input
 /// * `layer_output_tx` - **Sender** del channel con il layer successivo, invia l'Evento rappresentante gli impulsi di output
-    pub fn process(&mut self, adder: Adder, multiplier:  Multiplier, layer_input_rc: Receiver<Evento>, layer_output_tx: Sender<Evento>){
+    pub fn process(&mut self, faulty_units: FaultyUnits, layer_input_rc: Receiver<Evento>, layer_output_tx: Sender<Evento>){
+        let healthy_units = ArithmeticUnits::with_format(self.format);
 
         /* Prendiamo l'output del layer precedente */
         while let Ok(input_spike) = layer_input_rc.recv() {
-            let mut local_adder=  adder;
-            let mut local_mult = multiplier;
             let mut at_least_one_spike = false;
 
             let instant = input_spike.ts;
             let mut output_spikes = Vec::<u8>::with_capacity(self.neurons.len());
-            /* controlliamo che non vi sia un transient bit-flip in questo determinato istante */
-            let check_res =self.check_transient_error(instant, &mut adder.clone(), &mut multiplier.clone());
-            match check_res{
-                None=>{}
-                Some((adder_new, mult_new))=>{
-                    /* Se si verifica un errore transitorio sul sommatore o moltiplicatore,
-                    per questo istante di tempo utilizzeremo delle copie dei due componenti
-                    a cui è stato inserito l'errore*/
-                    local_adder = adder_new;
-                    local_mult = mult_new;
-                }
-            }
+            /* controlliamo che non vi siano errori transitori o intermittenti in questo determinato istante:
+                quelli sui blocchi elaborativi vengono inseriti in una copia dei componenti valida solo per questo istante */
+            let mut local_units = faulty_units.clone();
+            self.check_transient_error(instant, &mut local_units);
 
             /* Processiamo l'input per ogni neurone nel layer */
             for (n_index, neuron) in self.neurons.iter_mut().enumerate(){
+                /* il neurone utilizza i blocchi elaborativi con errore solo se è assegnato a un'unità affetta */
+                let units = local_units.get(&self.units[n_index]).copied().unwrap_or(healthy_units);
                 let mut intra_weights_sum = 0f64;
                 let mut extra_weights_sum = 0f64;
+                /* gli accumulatori protetti non sono soggetti agli errori del sommatore,
+                    ma ogni somma parziale viene comunque memorizzata nel formato del datapath */
                 /* Somma pesata degli ingressi al neurone in base agli extra-weights */
                 for (w_index, weight) in self.weights[n_index].iter().enumerate(){
                     if input_spike.spikes[w_index] != 0 {
-                        extra_weights_sum += weight;
+                        extra_weights_sum = if self.accumulation.extra_faultable() {
+                            units.adder.add(extra_weights_sum, *weight)
+                        } else {
+                            self.format.quantize(extra_weights_sum + weight)
+                        };
                     }
                 }
                 /* Somma pesata degli effetti dell'output precedente del neurone,
                     dipendente dagli intra-weights */
                 for (i_index, intra) in self.intra_weights[n_index].iter().enumerate(){
                     if i_index != n_index && self.prev_output[i_index] != 0{
-                        intra_weights_sum+= intra;
+                        intra_weights_sum = if self.accumulation.intra_faultable() {
+                            units.adder.add(intra_weights_sum, *intra)
+                        } else {
+                            self.format.quantize(intra_weights_sum + intra)
+                        };
                     }
                 }
                 /* Calcoliamo il potenziale di membrana e l'output del neurone */
-                let neuron_spike = neuron.update_v_mem(instant,intra_weights_sum, extra_weights_sum, local_adder.clone(), local_mult.clone());
+                let neuron_spike = neuron.update_v_mem(instant,intra_weights_sum, extra_weights_sum, units.adder, units.mult, units.exp_unit, units.cmp);
                 /* Salvataggio dell'output del neurone nel vettore contenente l'output totale del layer */
                 output_spikes.push(neuron_spike);
 
@@ -214,7 +319,7 @@ impl<N: Neuron+ Clone+'static> Layer<N> {
 
             /* Mandiamo l'output al prossimo layer */
             layer_output_tx.send(output_spike)
-                .expect(&format!("ERROR: sending spike event at t={}", instant));
+                .unwrap_or_else(|_| panic!("ERROR: sending spike event at t={}", instant));
         }
     }
 /// Inizializzazione del layer; pulizia del vettore prev_output e re-inizializzazione di tutti i neuroni
@@ -231,7 +336,10 @@ impl <N: Neuron+Clone+'static> Clone for Layer<N>{
             weights: self.weights.clone(),
             intra_weights: self.intra_weights.clone(),
             prev_output: self.prev_output.clone(),
-            error: None,
+            accumulation: self.accumulation,
+            units: self.units.clone(),
+            format: self.format,
+            errors: vec![],
         }
     }
 }
@@ -1,8 +1,13 @@
 pub mod neuron; /* pubblico */
 pub mod snn_builder; /*privato */
+pub mod network_file;
+pub mod dyn_snn;
     mod layer;
     mod processor;
-    mod snn;
+#[allow(clippy::module_inception)]
+pub mod snn;
+
+use serde::{Deserialize, Serialize};
 
 
 /// Struttura che rappresenta gli impulsi scambiati tra i layer della rete
@@ -16,4 +21,31 @@ impl Evento {
     pub fn new(ts: u64, spikes: Vec<u8>) -> Self{
         Self{ ts, spikes }
     }
-}
\ No newline at end of file
+}
+
+/// Accumulazioni delle somme pesate di un layer che vengono eseguite dal sommatore della rete,
+/// e che quindi possono essere affette dai suoi errori; le altre vengono eseguite senza errori
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccumulationMode {
+    /// nessuna accumulazione passa dal sommatore (accumulatori protetti)
+    Protected,
+    /// solo la somma dei pesi esterni passa dal sommatore
+    ExtraOnly,
+    /// solo la somma dei pesi interni passa dal sommatore
+    IntraOnly,
+    /// entrambe le somme passano dal sommatore
+    #[default]
+    All,
+}
+
+impl AccumulationMode {
+/// Ritorna `true` se la somma dei pesi esterni passa dal sommatore
+    pub fn extra_faultable(&self) -> bool {
+        matches!(self, AccumulationMode::ExtraOnly | AccumulationMode::All)
+    }
+/// Ritorna `true` se la somma dei pesi interni passa dal sommatore
+    pub fn intra_faultable(&self) -> bool {
+        matches!(self, AccumulationMode::IntraOnly | AccumulationMode::All)
+    }
+}
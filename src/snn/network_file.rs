@@ -0,0 +1,295 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use crate::snn::neuron::Neuron;
+use crate::snn::snn_builder::{SnnBuilder, SnnParams};
+use crate::models::lifneuron::LIFNeuron;
+use crate::models::alifneuron::ALIFNeuron;
+use crate::models::izhikevichneuron::IzhikevichNeuron;
+
+/// Formato del file contenente la definizione della rete (o una lista di errori)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat{
+    Toml,
+    Json
+}
+
+impl FileFormat {
+/// Ricava il formato del file dalla sua estensione (`.toml` o `.json`)
+    pub fn from_path(path: &Path) -> Result<Self, NetworkFileError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(FileFormat::Toml),
+            Some("json") => Ok(FileFormat::Json),
+            _ => Err(NetworkFileError::UnsupportedFormat(path.display().to_string()))
+        }
+    }
+}
+
+/// Errori che possono verificarsi durante la lettura o la scrittura su file di una rete, degli impulsi in ingresso
+/// o di una lista di errori
+#[derive(Debug)]
+pub enum NetworkFileError{
+    /// Errore di lettura/scrittura del file
+    Io(std::io::Error),
+    /// Il file non ha estensione `.toml` o `.json`
+    UnsupportedFormat(String),
+    /// Il contenuto del file non rispetta il formato atteso
+    Parse(String),
+    /// Errore durante la serializzazione della rete o della lista di errori
+    Serialize(String),
+    /// Il modello di neurone indicato nel file è diverso da quello della rete da costruire
+    ModelMismatch{expected: NeuronModel, found: NeuronModel}
+}
+
+impl Display for NetworkFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkFileError::Io(e) => write!(f, "I/O error: {}", e),
+            NetworkFileError::UnsupportedFormat(path) => write!(f, "unsupported file format: {} (expected .toml or .json)", path),
+            NetworkFileError::Parse(e) => write!(f, "invalid file content: {}", e),
+            NetworkFileError::Serialize(e) => write!(f, "unable to serialize: {}", e),
+            NetworkFileError::ModelMismatch{expected, found} =>
+                write!(f, "the file describes a network of {} neurons, expected {} neurons", found, expected),
+        }
+    }
+}
+
+impl std::error::Error for NetworkFileError {}
+
+impl From<std::io::Error> for NetworkFileError {
+    fn from(e: std::io::Error) -> Self {
+        NetworkFileError::Io(e)
+    }
+}
+
+/// Modello dei neuroni di una rete, indicato nel file dalla chiave `model` (`lif` se assente)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NeuronModel{
+    #[default]
+    Lif,
+    Alif,
+    Izhikevich
+}
+
+impl Display for NeuronModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NeuronModel::Lif => write!(f, "lif"),
+            NeuronModel::Alif => write!(f, "alif"),
+            NeuronModel::Izhikevich => write!(f, "izhikevich"),
+        }
+    }
+}
+
+/// Tratto che associa a ciascun tipo di neurone il modello con cui compare nei file di definizione della rete
+pub trait NetworkModel {
+    const MODEL: NeuronModel;
+}
+
+impl NetworkModel for LIFNeuron {
+    const MODEL: NeuronModel = NeuronModel::Lif;
+}
+
+impl NetworkModel for ALIFNeuron {
+    const MODEL: NeuronModel = NeuronModel::Alif;
+}
+
+impl NetworkModel for IzhikevichNeuron {
+    const MODEL: NeuronModel = NeuronModel::Izhikevich;
+}
+
+/// Intestazione del file di definizione della rete: permette di conoscere il modello dei neuroni
+/// prima di leggere i loro parametri
+#[derive(Debug, Deserialize)]
+struct NetworkHeader{
+    #[serde(default)]
+    model: NeuronModel,
+}
+
+impl NeuronModel {
+/// Legge il modello dei neuroni della rete definita nel file `path`, in modo da scegliere il tipo di neurone
+/// con cui costruirla; il formato (TOML o JSON) viene dedotto dall'estensione del file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<NeuronModel, NetworkFileError> {
+        let header: NetworkHeader = read_file(path.as_ref())?;
+        Ok(header.model)
+    }
+}
+
+/// Descrizione di un layer all'interno del file
+/// # Campi
+/// * `neurons` - parametri di ciascun neurone del layer
+/// * `extra_weights` - pesi tra i neuroni del layer e quelli del layer precedente
+///   (una riga per ciascun neurone del layer)
+/// * `intra_weights` - pesi tra i neuroni del layer stesso
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerFile<N>{
+    pub neurons: Vec<N>,
+    pub extra_weights: Vec<Vec<f64>>,
+    pub intra_weights: Vec<Vec<f64>>,
+}
+
+/// Descrizione completa di una rete all'interno del file; i layer sono in ordine,
+/// dal primo (che riceve l'input della rete) all'ultimo (che produce l'output)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkFile<N>{
+    #[serde(default)]
+    pub model: NeuronModel,
+    pub layers: Vec<LayerFile<N>>,
+}
+
+/// Impulsi in ingresso alla rete contenuti nel file: un vettore di impulsi (`0` o `1`) per ciascun istante
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputFile{
+    pub spikes: Vec<Vec<u8>>,
+}
+
+impl InputFile {
+/// Legge gli impulsi in ingresso dal file `path`; il formato (TOML o JSON) viene dedotto dall'estensione del file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<InputFile, NetworkFileError> {
+        let path = path.as_ref();
+        let format = FileFormat::from_path(path)?;
+        let content = fs::read_to_string(path)?;
+        match format {
+            FileFormat::Toml => toml::from_str(&content).map_err(|e| NetworkFileError::Parse(e.to_string())),
+            FileFormat::Json => serde_json::from_str(&content).map_err(|e| NetworkFileError::Parse(e.to_string())),
+        }
+    }
+}
+
+impl <N: Neuron+Clone+Debug+NetworkModel+'static> NetworkFile<N> {
+/// Converte la descrizione letta da file nei parametri di costruzione della rete
+    pub fn into_params(self) -> SnnParams<N> {
+        let mut neurons = Vec::with_capacity(self.layers.len());
+        let mut extra_weights = Vec::with_capacity(self.layers.len());
+        let mut intra_weights = Vec::with_capacity(self.layers.len());
+        for layer in self.layers {
+            neurons.push(layer.neurons);
+            extra_weights.push(layer.extra_weights);
+            intra_weights.push(layer.intra_weights);
+        }
+        SnnParams::new(neurons, extra_weights, intra_weights)
+    }
+/// Crea la descrizione da salvare su file a partire dai parametri di costruzione della rete
+    pub fn from_params(params: &SnnParams<N>) -> Self {
+        let layers = params.neurons().iter()
+            .zip(params.extra_weights().iter())
+            .zip(params.intra_weights().iter())
+            .map(|((neurons, extra), intra)| LayerFile {
+                neurons: neurons.clone(),
+                extra_weights: extra.clone(),
+                intra_weights: intra.clone(),
+            })
+            .collect();
+        Self { model: N::MODEL, layers }
+    }
+}
+
+/// Legge il contenuto del file `path`, il cui formato (TOML o JSON) viene dedotto dall'estensione
+fn read_file<T: DeserializeOwned>(path: &Path) -> Result<T, NetworkFileError> {
+    let format = FileFormat::from_path(path)?;
+    let content = fs::read_to_string(path)?;
+    match format {
+        FileFormat::Toml => toml::from_str(&content).map_err(|e| NetworkFileError::Parse(e.to_string())),
+        FileFormat::Json => serde_json::from_str(&content).map_err(|e| NetworkFileError::Parse(e.to_string())),
+    }
+}
+
+impl <N: Neuron+Clone+Debug+Serialize+DeserializeOwned+NetworkModel+'static> SnnBuilder<N> {
+/// Crea un `SnnBuilder` a partire dalla definizione della rete contenuta nel file `path`.
+/// Il formato (TOML o JSON) viene dedotto dall'estensione del file; se il file descrive una rete
+/// di un modello di neurone diverso da `N` viene ritornato `NetworkFileError::ModelMismatch`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<SnnBuilder<N>, NetworkFileError> {
+        let path = path.as_ref();
+        /* il modello viene controllato prima di leggere i parametri dei neuroni */
+        let model = NeuronModel::from_file(path)?;
+        if model != N::MODEL {
+            return Err(NetworkFileError::ModelMismatch { expected: N::MODEL, found: model });
+        }
+        let network: NetworkFile<N> = read_file(path)?;
+        Ok(SnnBuilder::from_params(network.into_params()))
+    }
+/// Salva la definizione della rete costruita fino a questo momento nel file `path`.
+/// Il formato (TOML o JSON) viene dedotto dall'estensione del file
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), NetworkFileError> {
+        let path = path.as_ref();
+        let format = FileFormat::from_path(path)?;
+        let network = NetworkFile::from_params(&self.get_params());
+        let content = match format {
+            FileFormat::Toml => toml::to_string(&network).map_err(|e| NetworkFileError::Serialize(e.to_string()))?,
+            FileFormat::Json => serde_json::to_string_pretty(&network).map_err(|e| NetworkFileError::Serialize(e.to_string()))?,
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::lifneuron::{IntegrationScheme, ResetMode};
+    use std::path::PathBuf;
+
+    /// Percorso di un file temporaneo per il test `name`
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("group12_{}_{}", std::process::id(), name))
+    }
+
+    /// Parametri costanti di un `LIFNeuron`, per confrontare due reti
+    fn lif_params(neuron: &LIFNeuron) -> (f64, f64, f64, f64, f64, u64, ResetMode, IntegrationScheme) {
+        (neuron.get_th(), neuron.v_rest(), neuron.v_reset(), neuron.tau(), neuron.d_t(), neuron.t_ref(),
+         neuron.reset_mode(), neuron.integration())
+    }
+
+    #[test]
+    fn network_round_trip() {
+        let mut builder = SnnBuilder::<LIFNeuron>::new();
+        builder.add_layer().add_weight([[0.1, 0.2], [0.3, 0.4]]).unwrap()
+            .add_neurons([
+                LIFNeuron::new(0.03, 0.05, 0.1, 1.0, 1.0).with_refractory(2),
+                LIFNeuron::new(0.05, 0.01, 0.2, 2.5, 0.5).with_reset_mode(ResetMode::Subtraction)
+                    .with_integration(IntegrationScheme::ShiftLeak(3)),
+            ]).unwrap()
+            .add_intra_weights([[0.0, -0.25], [-0.1, 0.0]]).unwrap()
+        .add_layer().add_weight([[0.5, 0.6]]).unwrap()
+            .add_neurons([LIFNeuron::new(0.09, 0.06, 0.4, 1.0, 1.0).with_integration(IntegrationScheme::Euler)]).unwrap()
+            .add_intra_weights([[0.0]]).unwrap();
+        let original = builder.get_params();
+        for name in ["round_trip.toml", "round_trip.json"] {
+            let path = temp_path(name);
+            builder.to_file(&path).unwrap();
+            let loaded = SnnBuilder::<LIFNeuron>::from_file(&path);
+            fs::remove_file(&path).unwrap();
+            let loaded = loaded.unwrap().get_params();
+            assert_eq!(loaded.extra_weights(), original.extra_weights(), "{}", name);
+            assert_eq!(loaded.intra_weights(), original.intra_weights(), "{}", name);
+            let neurons = |params: &SnnParams<LIFNeuron>| params.neurons().iter()
+                .map(|layer| layer.iter().map(lif_params).collect::<Vec<_>>()).collect::<Vec<_>>();
+            assert_eq!(neurons(&loaded), neurons(&original), "{}", name);
+        }
+        assert!(matches!(builder.to_file(temp_path("network.yaml")), Err(NetworkFileError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn network_model_is_checked() {
+        let path = temp_path("alif.toml");
+        let mut builder = SnnBuilder::<ALIFNeuron>::new();
+        builder.add_layer().add_weight([[0.5]]).unwrap()
+            .add_neurons([ALIFNeuron::new(1.0, 0.0, 0.0, 1.0, 1.0, 10.0, 0.5)]).unwrap()
+            .add_intra_weights([[0.0]]).unwrap();
+        builder.to_file(&path).unwrap();
+        assert_eq!(NeuronModel::from_file(&path).unwrap(), NeuronModel::Alif);
+        assert!(SnnBuilder::<ALIFNeuron>::from_file(&path).is_ok());
+        let result = SnnBuilder::<LIFNeuron>::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(NetworkFileError::ModelMismatch { expected: NeuronModel::Lif, found: NeuronModel::Alif })));
+        /* senza la chiave `model` la rete è di LIFNeuron */
+        let path = temp_path("no_model.json");
+        fs::write(&path, r#"{"layers": []}"#).unwrap();
+        let model = NeuronModel::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(model.unwrap(), NeuronModel::Lif);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::error_handling::components::Adder;
 use crate::error_handling::components::Multiplier;
+use crate::error_handling::components::ExpUnit;
+use crate::error_handling::components::Comparator;
+use crate::error_handling::numeric_format::NumericFormat;
 /// Tratto generico che rappresenta il neurone di una rete
 pub trait Neuron: Send{
 /// Funzione per calcolare il nuovo potenziale di membrana del neurone;
@@ -8,14 +11,25 @@ pub trait Neuron: Send{
 /// * `t` - Instante di tempo corrente
 /// * `intra_weight` - Somma pesata dei segnali provenienti dal layer stesso
 /// * `extra_weight` - Somma pesata dei segnali provenienti dal layer precedente
-    fn update_v_mem(&mut self, t: u64, intra_weight: f64, extra_weight: f64, adder:  Adder, mult: Multiplier)->u8;
+/// * `adder` - Componente Sommatore
+/// * `mult` - Componente Moltiplicatore
+/// * `exp_unit` - Componente per il calcolo dell'esponenziale
+/// * `cmp` - Componente Comparatore per il confronto con la soglia
+    #[allow(clippy::too_many_arguments)]
+    fn update_v_mem(&mut self, t: u64, intra_weight: f64, extra_weight: f64, adder:  Adder, mult: Multiplier, exp_unit: ExpUnit, cmp: Comparator)->u8;
 /// Funzione per resettare i parametri del neurone a quelli iniziali
     fn init_neuron(&mut self);
 /// Funzione per per settare un errore stuck-at-X sul potenziale di membrana
 /// # Argomenti
 /// * `error_type` - valore a cui il bit è bloccato *(0/1)*
-/// * `position` - posizione del bit bloccato
-    fn set_membrane_error(&mut self, error_type:u8, position:u8);
+/// * `mask` - maschera dei bit bloccati
+/// * `format` - formato numerico in cui è memorizzato il potenziale
+    fn set_membrane_error(&mut self, error_type:u8, mask:u64, format: NumericFormat);
+/// Memorizza i parametri costanti e lo stato del neurone nel formato numerico del datapath `format`,
+/// i.e. li arrotonda al valore rappresentabile più vicino. Di default arrotonda solo il potenziale di soglia
+    fn quantize(&mut self, format: NumericFormat) {
+        self.set_th(format.quantize(self.get_th()));
+    }
 /// Ritorna il valore del potenziale di soglia
     fn get_th(&self) -> f64;
 /// Setta il valore del potenziale di soglia
@@ -24,5 +38,37 @@ pub trait Neuron: Send{
     fn get_mem(&self) -> f64;
 /// Setta il valore del potenziale di membrana
     fn set_mem(&mut self, new_mem: f64);
+/// Funzione per settare un errore stuck-at-X sul contatore del periodo refrattario.
+/// Di default non fa nulla, per i modelli di neurone senza periodo refrattario
+/// # Argomenti
+/// * `error_type` - valore a cui il bit è bloccato *(0/1)*
+/// * `mask` - maschera dei bit bloccati
+    fn set_refractory_error(&mut self, _error_type:u8, _mask:u64){}
+/// Ritorna il valore del contatore del periodo refrattario, i.e. il numero di istanti
+/// in cui il neurone ignorerà ancora il proprio input
+    fn get_refractory(&self) -> u64 { 0 }
+/// Setta il valore del contatore del periodo refrattario
+    fn set_refractory(&mut self, _new_counter: u64){}
+/// Funzione per settare un errore stuck-at-X sulla variabile di stato ausiliaria del modello
+/// (e.g. variabile di recupero `u` del neurone di Izhikevich).
+/// Di default non fa nulla, per i modelli di neurone senza variabili di stato ausiliarie
+/// # Argomenti
+/// * `error_type` - valore a cui il bit è bloccato *(0/1)*
+/// * `mask` - maschera dei bit bloccati
+/// * `format` - formato numerico in cui è memorizzata la variabile
+    fn set_aux_error(&mut self, _error_type:u8, _mask:u64, _format: NumericFormat){}
+/// Ritorna il valore della variabile di stato ausiliaria
+    fn get_aux(&self) -> f64 { 0.0 }
+/// Setta il valore della variabile di stato ausiliaria
+    fn set_aux(&mut self, _new_aux: f64){}
+/// Ritorna `true` se il modello ha una variabile di stato ausiliaria, i.e. se gli errori sul componente `9`
+/// hanno effetto. Di default `false`
+    fn has_aux(&self) -> bool { false }
+/// Ritorna `true` se l'aggiornamento del neurone utilizza l'unità esponenziale, i.e. se gli errori sui componenti
+/// `10` e `11` hanno effetto (e.g. non nell'integrazione di Eulero). Di default `true`
+    fn uses_exp_unit(&self) -> bool { true }
+/// Ritorna `true` se l'aggiornamento del neurone utilizza il moltiplicatore, i.e. se gli errori sui componenti
+/// `6` e `7` hanno effetto (e.g. non nel decadimento tramite shift). Di default `true`
+    fn uses_multiplier(&self) -> bool { true }
 
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::sync::{Arc, Mutex};
 use std::sync::mpsc::channel;
 use std::thread;
 use std::thread::JoinHandle;
-use crate::error_handling::components::{Adder, Multiplier};
+use crate::error_handling::components::FaultyUnits;
 use crate::snn::Evento;
 use crate::snn::layer::Layer;
 use crate::snn::neuron::Neuron;
@@ -19,10 +19,9 @@ impl Processor {
 /// # Argomenti
 /// * `snn` - rete neurale che deve processare gli impulsi
 /// * `spikes` - vettore degli Eventi in input; ogni Evento un vettore di impulsi in ingresso ad un determinato istante
-/// * `adder` - componente Sommatore della rete
-/// * `mult` - componente Moltiplicatore della rete
+/// * `faulty_units` - blocchi elaborativi affetti da errori permanenti, indicizzati dall'unità a cui appartengono
     pub fn process_events<'a, N: Neuron+Clone+'static, S: IntoIterator<Item=&'a mut Arc<Mutex<Layer<N>>>>>
-        (self, snn: S, spikes: Vec<Evento>, adder: Adder , mult:  Multiplier) -> Vec<Evento>{
+        (self, snn: S, spikes: Vec<Evento>, faulty_units: FaultyUnits) -> Vec<Evento>{
         /* Creiamo la pool di tutti i thread */
          let mut threads  = Vec::<JoinHandle<()>>::new();
 
@@ -36,6 +35,7 @@ impl Processor {
         for layer_ref in snn {
 
             let layer_ref = layer_ref.clone();
+            let faulty_units = faulty_units.clone();
             /* layer_tx manderà l'output di questo layer al next_layer_rc, ovvero il receiver del prossimo layer */
             let (layer_tx, next_layer_rc) = channel::<Evento>();
 
@@ -44,7 +44,7 @@ impl Processor {
                 /* Blocchiamo il layer in considerazione */
                 let mut layer = layer_ref.lock().unwrap();
                 /* Eseguiamo il compito del layer */
-                layer.process(adder,mult,layer_rc, layer_tx);
+                layer.process(faulty_units, layer_rc, layer_tx);
             });
             /* Inseriamo il thread all'interno del vettore con tutti i thread creati */
             threads.push(thread);
@@ -63,7 +63,7 @@ impl Processor {
             /* Mandiamo l'input al primo layer; la precedente creazione
                 dei channels farà in modo che gli impulsi vengano propagati nella rete */
             net_input_tx.send(evento)
-                .expect(&format!("ERROR: sending spikes event at t={}", instant));
+                .unwrap_or_else(|_| panic!("ERROR: sending spikes event at t={}", instant));
             // generiamo un messaggio di errore particolare in caso di errore **/
         }
         /* droppando il Sender al primo layer, faremo terminare in cascata tutti i thread */
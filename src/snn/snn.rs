@@ -2,56 +2,62 @@ use std::slice::IterMut;
 use std::sync::{Arc, Mutex};
 use crate::snn::layer::Layer;
 use crate::snn::neuron::Neuron;
-use crate::snn::Evento;
-use crate::snn::processor::Processor;
-use rand::Rng;
-use crate::error_handling::components::{Adder, Multiplier};
+use crate::snn::dyn_snn::{DynSNN, TransientErrorInfo};
+use crate::error_handling::components::FaultyUnits;
+use rand::rngs::StdRng;
 
 
-/// Struttura che rappresenta la rete neurale
+/// Struttura che rappresenta la rete neurale con dimensioni di input e output note a *compile time*.
+/// L'elaborazione è delegata a una `DynSNN` con le stesse dimensioni
 /// # Campi
-/// * `layers` - vettore dei layer che costituiscono la rete
-/// * `transient_error` - parametro opzionale temporaneo che contiene le informazioni relative a un possibile
-/// * `adder` - sommatore unico della rete
-/// * `multiplier` - moltiplicatore unico della rete
-/// errore transitorio, in attesa che venga selezionato un istante casuale
+/// * `network` - rete sottostante che contiene layer, eventuale errore transitorio e blocchi elaborativi
 /// # Tipi e costanti
 /// * `N` - tipo generico per rappresentare un Neurone
 /// * `SNN_INPUT_DIM` - dimensione dell'input della rete
 /// * `SNN_OUTPUT_DIM` - dimensione dell'output della rete
 pub struct SNN<N: Neuron + Clone+'static, const SNN_INPUT_DIM: usize, const SNN_OUTPUT_DIM: usize>{
-    layers: Vec<Arc<Mutex<Layer<N>>>>,
-    transient_error: Option<(usize, usize,i32, u8,(i32,i32))>,//layer, neuron, component, position, (input1, input2)
-    adder: Adder,
-    multiplier: Multiplier
+    network: DynSNN<N>
 }
 
 impl <N:Neuron + Clone+'static, const SNN_INPUT_DIM: usize, const SNN_OUTPUT_DIM: usize>
     SNN<N, SNN_INPUT_DIM, SNN_OUTPUT_DIM> {
-    pub fn new(layers: Vec<Arc<Mutex<Layer<N>>>>, transient_error: Option<(usize, usize, i32, u8, (i32,i32))>, adder: Adder, multiplier: Multiplier) -> Self {
+    pub fn new(layers: Vec<Arc<Mutex<Layer<N>>>>, transient_errors: Vec<Option<TransientErrorInfo>>, values: Vec<Option<(f64, f64)>>,
+               faulty_units: FaultyUnits, rng: StdRng) -> Self {
         Self {
-            layers,
-            transient_error,
-            adder,
-            multiplier,
+            network: DynSNN::new(layers, SNN_INPUT_DIM, SNN_OUTPUT_DIM, transient_errors, values, faulty_units, rng)
         }
     }
 
     pub fn layers(&self) -> &Vec<Arc<Mutex<Layer<N>>>> {
-        &self.layers
+        self.network.layers()
+    }
+/// Ritorna, per ciascun errore iniettato, l'istante iniziale e la durata dell'errore transitorio nell'ultima chiamata a `process()`
+    pub fn transient_windows(&self) -> &[Option<(u64, u64)>] {
+        self.network.transient_windows()
+    }
+/// Ritorna, per ciascun errore iniettato, il numero di istanti in cui si è attivato nell'ultima chiamata a `process()`
+    pub fn fires(&self) -> &[Option<u64>] {
+        self.network.fires()
+    }
+/// Ritorna, per ciascun errore iniettato, il valore originale e il valore corrotto del registro colpito
+    pub fn values(&self) -> &[Option<(f64, f64)>] {
+        self.network.values()
     }
 
 /// Funzione per processare gli impulsi in ingresso. Ritorna gli impulsi in uscita dalla rete.
-/// Se il parametro opzionale `transient_error` presenta dei valori, viene selezionato
-/// un istante casuale dipendente da `SPIKES_DURATION` in cui si presenterà l'errore transitorio.
+/// Per ciascun errore transitorio viene selezionato
+/// un istante casuale dipendente da `SPIKES_DURATION` in cui si presenterà l'errore transitorio;
+/// un errore su un blocco elaborativo resta attivo per la durata specificata, a partire da tale istante.
 /// # Argomenti
 /// * `input_spikes` - vettore di vettori di impulsi in istanti successivi
+/// # Panic
+/// Le dimensioni dell'input sono controllate a *compile-time*; va in panic se un impulso vale diverso da `0` e `1`
 /// # Costanti
 /// * `SPIKES_DURATION` - durata dell'ingresso, i.e. numero di istanti in cui viene fornito un input
 /// * `SNN_INPUT_DIM` - dimensione dell'input della rete
 /// * `SNN_OUTPUT_DIM` - dimensione dell'output della rete
 /// # Esempio di utilizzo
-/// ```
+/// ```ignore
 ///     snn.process(&[[0,1,1], [1,0,1]])
 /// ```
 /// implica `SNN_INPUT_DIM = 3` e `SPIKES_DURATION = 2`, i.e.:
@@ -59,63 +65,17 @@ impl <N:Neuron + Clone+'static, const SNN_INPUT_DIM: usize, const SNN_OUTPUT_DIM
 /// - All'istante `1`, l'ingresso della rete vale `[1,0,1]`
     pub fn process<const SPIKES_DURATION: usize>(&mut self, input_spikes: &[[u8; SNN_INPUT_DIM]; SPIKES_DURATION])
                                                  -> [[u8; SNN_OUTPUT_DIM]; SPIKES_DURATION] {
-        /* trasformiamo l'input in Eventi */
-        let input_events = SNN::<N, SNN_INPUT_DIM, SNN_OUTPUT_DIM>::spikes_to_events(input_spikes);
-        if self.transient_error.is_some(){
-            let (layer, neuron, component, position, input_errors)=self.transient_error.unwrap();
-            let mut rng=rand::thread_rng();
-            let random_instant:u64=rng.gen_range(0..SPIKES_DURATION) as u64;
-            /* settiamo l'errore transitorio sul layer corrispontente */
-            self.layers[layer].lock().unwrap().set_transient_error(neuron,component,position,random_instant, input_errors);
-        }
-        let processor = Processor {};
-        let adder = self.adder.clone();
-        let mult = self.multiplier.clone();
-        let output_events = processor.process_events(self, input_events, adder, mult);
+        let input: Vec<Vec<u8>> = input_spikes.iter().map(|spikes| spikes.to_vec()).collect();
+        let output = self.network.process(&input).unwrap_or_else(|e| panic!("Error: {}", e));
 
-        /* trasformiamo gli Eventi di output in vettori di segnali, in modo tale che
+        /* riportiamo l'output in forma di array, in modo tale che
             il valore di ritorno sia coerente con l'argomento in ingresso della funzione */
-        let output_spikes:[[u8; SNN_OUTPUT_DIM]; SPIKES_DURATION]
-            = SNN::<N, SNN_INPUT_DIM, SNN_OUTPUT_DIM>::spikes_from_events(output_events);
-
-        output_spikes
-    }
-
-
-/// Trasforma i vettori di segnali in ingresso in Eventi di impulsi che contegano le stesse informazioni.
-/// Controlla inoltre che i valori passati rappresentino effettivamente dei segnali, i.e. siano `0` o `1`
-    fn spikes_to_events<const SPIKES_DURATION: usize>
-        (spikes_matrix: &[[u8; SNN_INPUT_DIM]; SPIKES_DURATION]) -> Vec<Evento> {
-
-        let mut eventi = Vec::<Evento>::new();
-        for ts in 0..SPIKES_DURATION {
-            if spikes_matrix[ts].iter().any(|&s| s!=0 && s!=1){
-                panic!("Error: input spike must be 0 or 1 ");
-            }
-            let ts_spikes=spikes_matrix[ts].to_vec();
-            let evento_ts=Evento::new(ts as u64, ts_spikes);
-            eventi.push(evento_ts);
+        let mut output_spikes = [[0u8; SNN_OUTPUT_DIM]; SPIKES_DURATION];
+        for (ts, spikes) in output.into_iter().enumerate() {
+            output_spikes[ts].copy_from_slice(&spikes);
         }
 
-        eventi
-    }
-
-/// Trasforma gli Eventi in vettori di segnali
-    fn spikes_from_events<const SPIKES_DURATION: usize>
-        (eventi: Vec<Evento>) -> [[u8; SNN_OUTPUT_DIM]; SPIKES_DURATION] {
-
-        let mut raw_matrix = [[0u8; SNN_OUTPUT_DIM]; SPIKES_DURATION];
-
-        for evento in eventi {
-            if evento.spikes.len() != SNN_OUTPUT_DIM{
-                panic!("Error: spikes in the event should equal the output dimension")
-            }
-            for (n_index, spike) in evento.spikes.into_iter().enumerate() {
-                raw_matrix[evento.ts as usize][n_index] = spike;
-            }
-        }
-
-        raw_matrix
+        output_spikes
     }
 
 }
@@ -125,6 +85,6 @@ impl<'a, N: Neuron+Clone+'static, const SNN_INPUT_DIM: usize, const SNN_OUTPUT_D
     type IntoIter = IterMut<'a, Arc<Mutex<Layer<N>>>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.layers.iter_mut()
+        (&mut self.network).into_iter()
     }
-}
\ No newline at end of file
+}
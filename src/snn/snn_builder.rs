@@ -1,13 +1,26 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::sync::{Arc, Mutex};
 use crate::snn::layer::Layer;
 use crate::snn::neuron::Neuron;
+use crate::snn::AccumulationMode;
 use crate::snn::snn::SNN;
-use rand::Rng;
-use rand::rngs::ThreadRng;
-use crate::error_handling::error_handling;
+use crate::snn::dyn_snn::{DynSNN, TransientErrorInfo};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::error_handling::error_handling::{self, Upset};
 use crate::print_report::info_table::InfoTable;
-use crate::error_handling::components::{Adder, Multiplier};
+use crate::error_handling::components::{ArithmeticUnits, FaultyUnits, UnitAllocation};
+use crate::error_handling::numeric_format::{BitField, NumericFormat};
+use crate::error_handling::fault::{Fault, InjectionConfig};
+
+/// Layer della rete, condivisi tra i thread che li processano
+type Layers<N> = Vec<Arc<Mutex<Layer<N>>>>;
+/// Valore originale e valore corrotto del parametro colpito da ciascun errore, se noti alla costruzione
+type FaultValues = Vec<Option<(f64, f64)>>;
+/// Numero massimo di tentativi per posizionare un errore dell'insieme senza sovrapporlo a quelli già iniettati
+const MAX_PLACEMENT_ATTEMPTS: usize = 100;
+/// Numero di bit del contatore del periodo refrattario: è un registro intero, indipendente dal formato del datapath
+const REFRACTORY_WIDTH: u8 = 16;
 
 /// Enumeratore che identifica il tipo di errore da inserire nella rete
 pub enum ErrorComponent{
@@ -17,6 +30,118 @@ pub enum ErrorComponent{
     MembraneError,
     AdderOutputError
 }
+/// Matrice di pesi a cui si riferisce un `BuildError`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightMatrix{
+    /// pesi tra il layer e il layer precedente (o l'input della rete)
+    Extra,
+    /// pesi tra i neuroni dello stesso layer
+    Intra
+}
+
+impl Display for WeightMatrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightMatrix::Extra => write!(f, "extra weights"),
+            WeightMatrix::Intra => write!(f, "intra weights"),
+        }
+    }
+}
+
+/// Errori che possono verificarsi durante la costruzione della rete
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError{
+    /// La rete non contiene alcun layer
+    EmptyNetwork,
+    /// Il layer non contiene alcun neurone
+    EmptyLayer { layer: usize },
+    /// Il numero di layer di neuroni, pesi esterni e pesi interni non coincide
+    LayerCountMismatch { neurons: usize, extra_weights: usize, intra_weights: usize },
+    /// La matrice di pesi del layer non ha le dimensioni attese (righe x colonne)
+    WrongMatrixShape { layer: usize, matrix: WeightMatrix, expected: (usize, usize), found: (usize, usize) },
+    /// Peso esterno negativo; `neuron` è il neurone destinatario, `source` il neurone del layer precedente
+    NegativeExtraWeight { layer: usize, neuron: usize, source: usize, value: f64 },
+    /// Peso interno positivo; `neuron` è il neurone destinatario, `source` il neurone dello stesso layer
+    PositiveIntraWeight { layer: usize, neuron: usize, source: usize, value: f64 },
+    /// Peso interno non nullo sulla diagonale: il layer ignora il peso di un neurone verso se stesso
+    NonZeroIntraDiagonal { layer: usize, neuron: usize, value: f64 },
+    /// La dimensione del primo layer non corrisponde all'input della rete
+    InputDimMismatch { expected: usize, found: usize },
+    /// La dimensione dell'ultimo layer non corrisponde all'output della rete
+    OutputDimMismatch { expected: usize, found: usize },
+    /// Il formato numerico a virgola fissa non è contenuto in 64 bit (e.g. letto da file)
+    InvalidFormat { format: NumericFormat },
+    /// Nessun bit del formato numerico appartiene ai campi a cui è ristretta l'iniezione degli errori
+    NoBitsInFields { format: NumericFormat, fields: Vec<BitField> },
+    /// L'errore multi-bit non è contenuto in una parola del formato numerico
+    InvalidUpset { upset: Upset, width: u8 },
+    /// Nessun bit della maschera esplicita (`Upset::Mask`) appartiene ai campi a cui è ristretta l'iniezione degli errori
+    MaskOutsideFields { mask: u64, fields: Vec<BitField> },
+    /// La durata di un errore transitorio su un blocco elaborativo deve essere di almeno un istante
+    ZeroTransientDuration,
+    /// La probabilità di attivazione degli errori intermittenti non è in (0, 1]
+    InvalidProbability { probability: f64 },
+    /// L'insieme di errori iniettati in ciascuna rete deve contenere almeno un errore
+    EmptyFaultSet,
+    /// Uno dei tipi di errore tra cui scegliere non esiste (tipi validi: `0`, `1`, `2`)
+    InvalidErrorTypes { error_types: Vec<i32> },
+    /// Non è stato possibile posizionare tutti gli errori dell'insieme senza che due errori colpiscano
+    /// lo stesso registro di un neurone o lo stesso blocco elaborativo di un'unità
+    FaultSetTooLarge { requested: usize, placed: usize },
+    /// L'errore in posizione `index` dell'insieme (e.g. letto da una lista di errori su file) non corrisponde
+    /// a un componente della rete: componente, tipo, neurone, peso, maschera, errori sugli ingressi o istante iniziale
+    /// (oltre la durata dell'input, vedi `set_input_length()`) non validi
+    InvalidFault { index: usize, fault: Fault },
+    /// Nessuno dei componenti selezionati è utilizzato dal modello di neurone della rete
+    /// (e.g. la variabile di stato ausiliaria di un neurone LIF) o può contenere l'errore multi-bit scelto
+    UnsupportedComponents { components: Vec<i32> },
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::EmptyNetwork => write!(f, "the network must have at least one layer"),
+            BuildError::EmptyLayer { layer } => write!(f, "layer {} has no neurons", layer),
+            BuildError::LayerCountMismatch { neurons, extra_weights, intra_weights } =>
+                write!(f, "wrong number of layers: {} neuron layers, {} extra weights layers, {} intra weights layers", neurons, extra_weights, intra_weights),
+            BuildError::WrongMatrixShape { layer, matrix, expected, found } =>
+                write!(f, "layer {}: {} must be a {}x{} matrix, found {}x{}", layer, matrix, expected.0, expected.1, found.0, found.1),
+            BuildError::NegativeExtraWeight { layer, neuron, source, value } =>
+                write!(f, "layer {}: extra weights must be positive, found {} at [{}][{}]", layer, value, neuron, source),
+            BuildError::PositiveIntraWeight { layer, neuron, source, value } =>
+                write!(f, "layer {}: intra weights must be negative, found {} at [{}][{}]", layer, value, neuron, source),
+            BuildError::NonZeroIntraDiagonal { layer, neuron, value } =>
+                write!(f, "layer {}: intra weights must be zero on the diagonal, found {} at [{}][{}]", layer, value, neuron, neuron),
+            BuildError::InputDimMismatch { expected, found } =>
+                write!(f, "layer 0: extra weights have {} columns, but the network input dimension is {}", found, expected),
+            BuildError::OutputDimMismatch { expected, found } =>
+                write!(f, "last layer has {} neurons, but the network output dimension is {}", found, expected),
+            BuildError::InvalidFormat { format } =>
+                write!(f, "the {} format does not fit in 64 bits", format),
+            BuildError::NoBitsInFields { format, fields } =>
+                write!(f, "no bit of the {} format belongs to the fields {:?}", format, fields),
+            BuildError::InvalidUpset { upset, width } =>
+                write!(f, "the upset {:?} does not fit in a {}-bit word", upset, width),
+            BuildError::MaskOutsideFields { mask, fields } =>
+                write!(f, "no bit of the mask {:#x} belongs to the fields {:?}", mask, fields),
+            BuildError::ZeroTransientDuration => write!(f, "the transient duration must be at least one timestep"),
+            BuildError::InvalidProbability { probability } =>
+                write!(f, "the activation probability must be in (0, 1], found {}", probability),
+            BuildError::EmptyFaultSet => write!(f, "the fault set must contain at least one fault"),
+            BuildError::InvalidErrorTypes { error_types } =>
+                write!(f, "the error types must be in 0..=2, found {:?}", error_types),
+            BuildError::FaultSetTooLarge { requested, placed } =>
+                write!(f, "unable to place {} non-overlapping faults with the selected components, only {} placed", requested, placed),
+            BuildError::InvalidFault { index, fault } =>
+                write!(f, "fault {} does not target a component of the network: {:?}", index, fault),
+            BuildError::UnsupportedComponents { components } =>
+                write!(f, "none of the components {:?} can be injected: the neurons of the network do not use them, or the upset does not fit them", components),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
 /// Struttura che contiene i parametri della rete che si sta cotruendo
 #[derive(Debug, Clone)]
 pub struct SnnParams<N: Neuron+ Clone+Debug+'static>{
@@ -30,12 +155,47 @@ pub struct SnnParams<N: Neuron+ Clone+Debug+'static>{
     /// Vettori di pesi tra i neuroni dello stesso layer
     intra_weights: Vec<Vec<Vec<f64>>>,
 }
+
+impl <N: Neuron+ Clone+Debug> SnnParams<N> {
+/// Ritorna i parametri di una rete a partire dai vettori di neuroni e di pesi per ciascun layer
+    pub fn new(neurons: Vec<Vec<N>>, extra_weights: Vec<Vec<Vec<f64>>>, intra_weights: Vec<Vec<Vec<f64>>>) -> Self {
+        Self { neurons, extra_weights, intra_weights }
+    }
+    /*** Getters ***/
+    pub fn neurons(&self) -> &Vec<Vec<N>> {
+        &self.neurons
+    }
+    pub fn extra_weights(&self) -> &Vec<Vec<Vec<f64>>> {
+        &self.extra_weights
+    }
+    pub fn intra_weights(&self) -> &Vec<Vec<Vec<f64>>> {
+        &self.intra_weights
+    }
+}
 /// Struttura per creare la rete neurale aggiornando i suoi parametri
 #[derive(Debug, Clone)]
 pub struct SnnBuilder<N: Neuron+Clone+Debug+'static>{
     params: SnnParams<N>,
-    adder: Adder,
-    mult: Multiplier
+    units: ArithmeticUnits,
+    allocation: UnitAllocation,
+    faulty_units: FaultyUnits,
+    accumulation: AccumulationMode,
+    format: NumericFormat,
+    fields: Vec<BitField>,
+    upset: Upset,
+    transient_duration: u64,
+    intermittent: Option<f64>,
+    fault_count: usize,
+    error_types: Vec<i32>,
+    input_length: Option<u64>,
+    seed: Option<u64>,
+    rng: StdRng
+}
+
+impl<N: Neuron+ Clone+Debug> Default for SnnBuilder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl <N: Neuron+ Clone+Debug> SnnBuilder<N> {
@@ -47,72 +207,346 @@ impl <N: Neuron+ Clone+Debug> SnnBuilder<N> {
                 extra_weights: vec![],
                 intra_weights: vec![],
             },
-            adder: Adder::new(3,0),
-            mult: Multiplier::new(3,0),
+            units: ArithmeticUnits::default(),
+            allocation: UnitAllocation::default(),
+            faulty_units: FaultyUnits::new(),
+            accumulation: AccumulationMode::default(),
+            format: NumericFormat::default(),
+            fields: vec![],
+            upset: Upset::default(),
+            transient_duration: 1,
+            intermittent: None,
+            fault_count: 1,
+            error_types: vec![],
+            input_length: None,
+            seed: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+/// Crea un nuovo builder a partire da parametri di rete già esistenti (e.g. letti da file)
+    pub fn from_params(params: SnnParams<N>) -> Self {
+        Self {
+            params,
+            units: ArithmeticUnits::default(),
+            allocation: UnitAllocation::default(),
+            faulty_units: FaultyUnits::new(),
+            accumulation: AccumulationMode::default(),
+            format: NumericFormat::default(),
+            fields: vec![],
+            upset: Upset::default(),
+            transient_duration: 1,
+            intermittent: None,
+            fault_count: 1,
+            error_types: vec![],
+            input_length: None,
+            seed: None,
+            rng: StdRng::from_entropy(),
         }
     }
     pub fn get_params(&self) -> SnnParams<N> {
         self.params.clone()
     }
 
+/// Setta quali accumulazioni delle somme pesate dei layer passano dal sommatore della rete
+/// (di default `AccumulationMode::All`)
+    pub fn set_accumulation(&mut self, accumulation: AccumulationMode) -> &mut SnnBuilder<N> {
+        self.accumulation = accumulation;
+        self
+    }
+    pub fn accumulation(&self) -> AccumulationMode {
+        self.accumulation
+    }
+/// Setta il modello di allocazione dei blocchi elaborativi ai neuroni (di default `UnitAllocation::Global`)
+    pub fn set_allocation(&mut self, allocation: UnitAllocation) -> &mut SnnBuilder<N> {
+        self.allocation = allocation;
+        self
+    }
+    pub fn allocation(&self) -> UnitAllocation {
+        self.allocation
+    }
+/// Setta il formato numerico del datapath (di default `NumericFormat::Float64`).
+/// Pesi e parametri dei neuroni (soglia, potenziali di riposo e di reset, ...) vengono arrotondati al formato
+/// alla creazione dei layer, i blocchi elaborativi arrotondano ogni risultato e le posizioni degli errori sono scelte
+/// tra i bit validi per il formato; un formato a virgola fissa oltre i 64 bit viene rifiutato alla costruzione
+    pub fn set_format(&mut self, format: NumericFormat) -> &mut SnnBuilder<N> {
+        self.format = format;
+        self.units.set_format(format);
+        self
+    }
+    pub fn format(&self) -> NumericFormat {
+        self.format
+    }
+/// Restringe l'iniezione degli errori ai bit dei campi specificati (e.g. solo l'esponente);
+/// un vettore vuoto (predefinito) permette qualsiasi bit. La restrizione non riguarda i registri interi
+/// (contatore del periodo refrattario) e l'uscita del comparatore
+    pub fn set_fields(&mut self, fields: &[BitField]) -> &mut SnnBuilder<N> {
+        self.fields = fields.to_vec();
+        self
+    }
+    pub fn fields(&self) -> &[BitField] {
+        &self.fields
+    }
+/// Setta la forma degli errori iniettati (di default `Upset::Single`): un singolo bit, `N` bit adiacenti
+/// o una maschera arbitraria di bit della stessa parola, tutti forzati (stuck-at-X) o invertiti (bit-flip)
+    pub fn set_upset(&mut self, upset: Upset) -> &mut SnnBuilder<N> {
+        self.upset = upset;
+        self
+    }
+    pub fn upset(&self) -> Upset {
+        self.upset
+    }
+/// Setta il numero di istanti consecutivi in cui un blocco elaborativo resta affetto da un errore transitorio
+/// (di default `1`); non riguarda gli errori transitori sui registri, che ne modificano il valore una sola volta.
+/// Ritorna `BuildError::ZeroTransientDuration` se `duration` è `0`
+    pub fn set_transient_duration(&mut self, duration: u64) -> Result<&mut SnnBuilder<N>, BuildError> {
+        if duration == 0 {
+            return Err(BuildError::ZeroTransientDuration);
+        }
+        self.transient_duration = duration;
+        Ok(self)
+    }
+    pub fn transient_duration(&self) -> u64 {
+        self.transient_duration
+    }
+/// Rende intermittenti gli errori iniettati: in ciascun istante l'errore (di qualsiasi tipo) si attiva con probabilità
+/// `probability`, come accade per hardware marginale o degradato; `None` (predefinito) per errori permanenti e transitori.
+/// Ritorna `BuildError::InvalidProbability` se la probabilità non è in (0, 1]
+    pub fn set_intermittent(&mut self, probability: Option<f64>) -> Result<&mut SnnBuilder<N>, BuildError> {
+        if let Some(p) = probability.filter(|&p| !(p > 0.0 && p <= 1.0)) {
+            return Err(BuildError::InvalidProbability { probability: p });
+        }
+        self.intermittent = probability;
+        Ok(self)
+    }
+    pub fn intermittent(&self) -> Option<f64> {
+        self.intermittent
+    }
+/// Setta il numero di errori iniettati in ciascuna rete costruita (di default `1`), per studiare l'effetto
+/// di errori accumulati; gli errori dell'insieme possono colpire layer e componenti differenti,
+/// ma mai lo stesso registro di un neurone o lo stesso blocco elaborativo di un'unità.
+/// Ritorna `BuildError::EmptyFaultSet` se `count` è `0`
+    pub fn set_fault_count(&mut self, count: usize) -> Result<&mut SnnBuilder<N>, BuildError> {
+        if count == 0 {
+            return Err(BuildError::EmptyFaultSet);
+        }
+        self.fault_count = count;
+        Ok(self)
+    }
+    pub fn fault_count(&self) -> usize {
+        self.fault_count
+    }
+/// Setta i tipi di errore tra cui scegliere casualmente per ciascun errore dell'insieme (e.g. `&[0, 2]` per
+/// mescolare stuck-at-0 e bit-flip); un vettore vuoto (predefinito) usa per tutti il tipo passato a `build()`.
+/// Ritorna `BuildError::InvalidErrorTypes` se uno dei tipi non esiste
+    pub fn set_error_types(&mut self, error_types: &[i32]) -> Result<&mut SnnBuilder<N>, BuildError> {
+        if !error_types.iter().all(|e| (0..=2).contains(e)) {
+            return Err(BuildError::InvalidErrorTypes { error_types: error_types.to_vec() });
+        }
+        self.error_types = error_types.to_vec();
+        Ok(self)
+    }
+    pub fn error_types(&self) -> &[i32] {
+        &self.error_types
+    }
+/// Applica in una volta sola tutta la configurazione dell'iniezione degli errori (e.g. letta da riga di comando
+/// o salvata con una lista di errori): formato, campi, forma degli errori, durata dei transitori, probabilità
+/// degli errori intermittenti, dimensione e tipi di errore dell'insieme di errori, allocazione dei blocchi e accumulazioni.
+/// Ritorna un `BuildError` se uno dei valori non è valido (e.g. configurazione modificata a mano in una lista di errori)
+    pub fn set_injection_config(&mut self, config: &InjectionConfig) -> Result<&mut SnnBuilder<N>, BuildError> {
+        self.set_transient_duration(config.transient_duration)?
+            .set_intermittent(config.intermittent)?
+            .set_fault_count(config.fault_count)?
+            .set_error_types(&config.error_types)?
+            .set_format(config.format)
+            .set_fields(&config.fields)
+            .set_upset(config.upset)
+            .set_allocation(config.allocation)
+            .set_accumulation(config.accumulation);
+        Ok(self)
+    }
+/// Ritorna la configurazione corrente dell'iniezione degli errori
+    pub fn injection_config(&self) -> InjectionConfig {
+        InjectionConfig {
+            format: self.format,
+            fields: self.fields.clone(),
+            upset: self.upset,
+            transient_duration: self.transient_duration,
+            intermittent: self.intermittent,
+            fault_count: self.fault_count,
+            error_types: self.error_types.clone(),
+            allocation: self.allocation,
+            accumulation: self.accumulation,
+        }
+    }
+/// Setta la durata (numero di istanti) dell'input che verrà processato dalla rete: gli errori forniti con
+/// `build_with_faults()` o `build_dyn_with_faults()` (e.g. letti da file) devono iniziare entro l'input.
+/// Senza durata (predefinito) l'istante iniziale degli errori non viene controllato
+    pub fn set_input_length(&mut self, length: u64) -> &mut SnnBuilder<N> {
+        self.input_length = Some(length);
+        self
+    }
+    pub fn input_length(&self) -> Option<u64> {
+        self.input_length
+    }
+/// Setta il seme del generatore di numeri casuali usato per scegliere gli errori e, nella rete costruita,
+/// gli istanti in cui si attivano gli errori transitori e intermittenti: a parità di seme, rete e configurazione
+/// viene riprodotta la stessa inferenza. Senza seme (predefinito) il generatore è inizializzato casualmente
+    pub fn set_seed(&mut self, seed: u64) -> &mut SnnBuilder<N> {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+/// Ritorna il generatore passato alla rete costruita, ricavato dallo stato corrente del generatore del builder,
+/// che viene fatto avanzare: costruzioni successive con lo stesso builder scelgono errori e istanti differenti
+    fn network_rng(&mut self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.gen())
+    }
+/// Ritorna l'indice dell'unità di blocchi elaborativi assegnata al neurone `neuron_index` del layer `layer_index`,
+/// secondo il modello di allocazione del builder
+    fn unit_index(&self, layer_index: usize, neuron_index: usize) -> usize {
+        match self.allocation {
+            UnitAllocation::Global => 0,
+            UnitAllocation::PerLayer => layer_index,
+            /* i neuroni sono numerati consecutivamente su tutta la rete */
+            UnitAllocation::PerNeuron => self.params.neurons[..layer_index].iter().map(|layer| layer.len()).sum::<usize>() + neuron_index,
+        }
+    }
+
     /** Serve solo logicamente */
     pub fn add_layer(&mut self) -> &mut SnnBuilder<N> {
         self
     }
 
 /// Aggiunge un nuovo layer di pesi esterni e controlla che siano tutti pesi positivi
+/// e che il numero di colonne corrisponda alla dimensione del layer precedente, se già aggiunto
 /// # Argomenti
 /// * `weights` - vettori di pesi tra i neuroni dell'ultimo layer con i neuroni del layer precedente
-    pub fn add_weight<const NUM_NEURONS: usize, const PREVIOUS_DIM: usize >(&mut self, weights:[[f64; PREVIOUS_DIM]; NUM_NEURONS]) -> &mut SnnBuilder<N> {
-        let mut new_weights = <Vec<Vec<f64>>>::new();
+    pub fn add_weight<const NUM_NEURONS: usize, const PREVIOUS_DIM: usize >(&mut self, weights:[[f64; PREVIOUS_DIM]; NUM_NEURONS]) -> Result<&mut SnnBuilder<N>, BuildError> {
+        let layer = self.params.extra_weights.len();
+        let new_weights: Vec<Vec<f64>> = weights.iter().map(|n_weight| n_weight.to_vec()).collect();
 
-        for n_weight in &weights{
-            for w in n_weight{
-                if w < &0.0{
-                    panic!("Pesi devono essere positivi!");
-                }
+        SnnBuilder::<N>::check_extra_signs(layer, &new_weights)?;
+        if layer > 0 {
+            if let Some(previous) = self.params.neurons.get(layer - 1) {
+                SnnBuilder::<N>::check_shape(layer, WeightMatrix::Extra, &new_weights, (NUM_NEURONS, previous.len()))?;
             }
-            new_weights.push(Vec::from(n_weight.as_slice()));
+        }
+        if let Some(neurons) = self.params.neurons.get(layer) {
+            SnnBuilder::<N>::check_shape(layer, WeightMatrix::Extra, &new_weights, (neurons.len(), PREVIOUS_DIM))?;
         }
         self.params.extra_weights.push(new_weights);
-        self
+        Ok(self)
     }
-/// Aggiunge un nuovo layer di neuroni
-    pub fn add_neurons<const NUM_NEURONS: usize>(&mut self, neurons: [N; NUM_NEURONS]) -> &mut SnnBuilder<N> {
+/// Aggiunge un nuovo layer di neuroni e controlla che sia coerente con i pesi del layer già aggiunti
+    pub fn add_neurons<const NUM_NEURONS: usize>(&mut self, neurons: [N; NUM_NEURONS]) -> Result<&mut SnnBuilder<N>, BuildError> {
+        let layer = self.params.neurons.len();
+        if NUM_NEURONS == 0 {
+            return Err(BuildError::EmptyLayer { layer });
+        }
+        if let Some(extra) = self.params.extra_weights.get(layer) {
+            let columns = extra.first().map(|row| row.len()).unwrap_or(0);
+            SnnBuilder::<N>::check_shape(layer, WeightMatrix::Extra, extra, (NUM_NEURONS, columns))?;
+        }
+        if let Some(intra) = self.params.intra_weights.get(layer) {
+            SnnBuilder::<N>::check_shape(layer, WeightMatrix::Intra, intra, (NUM_NEURONS, NUM_NEURONS))?;
+        }
         self.params.neurons.push(Vec::from(neurons));
-        self
+        Ok(self)
     }
 /// Aggiunge un nuovo layer di pesi interni e controlla che siano tutti pesi negativi
+/// e che la dimensione corrisponda al numero di neuroni del layer, se già aggiunti
 /// # Argomenti
 /// * `intra_weights` - vettori di pesi tra i neuroni
-    pub fn add_intra_weights<const NUM_NEURONS: usize>(&mut self, intra_weights: [[f64; NUM_NEURONS]; NUM_NEURONS]) -> &mut SnnBuilder<N> {
-        let mut new_weights = <Vec<Vec<f64>>>::new();
+    pub fn add_intra_weights<const NUM_NEURONS: usize>(&mut self, intra_weights: [[f64; NUM_NEURONS]; NUM_NEURONS]) -> Result<&mut SnnBuilder<N>, BuildError> {
+        let layer = self.params.intra_weights.len();
+        let new_weights: Vec<Vec<f64>> = intra_weights.iter().map(|n_weight| n_weight.to_vec()).collect();
 
-        for n_weight in &intra_weights{
-            for w in n_weight{
-                if w>&0f64{
-                    panic!("Pesi nello stesso layer devono essere negativi!");
-                }
-            }
-            new_weights.push(Vec::from(n_weight.as_slice()));
+        SnnBuilder::<N>::check_intra_signs(layer, &new_weights)?;
+        if let Some(neurons) = self.params.neurons.get(layer) {
+            SnnBuilder::<N>::check_shape(layer, WeightMatrix::Intra, &new_weights, (neurons.len(), neurons.len()))?;
         }
         self.params.intra_weights.push(new_weights);
-        self
+        Ok(self)
+    }
+/// Controlla che la matrice di pesi `weights` del layer `layer` abbia dimensione `expected` (righe x colonne)
+    fn check_shape(layer: usize, matrix: WeightMatrix, weights: &[Vec<f64>], expected: (usize, usize)) -> Result<(), BuildError> {
+        let wrong_row = weights.iter().find(|row| row.len() != expected.1);
+        if weights.len() != expected.0 || wrong_row.is_some() {
+            /* come numero di colonne si riporta quello della prima riga errata, se presente */
+            let columns = wrong_row.or(weights.first()).map(|row| row.len()).unwrap_or(0);
+            return Err(BuildError::WrongMatrixShape { layer, matrix, expected, found: (weights.len(), columns) });
+        }
+        Ok(())
+    }
+/// Controlla che i pesi esterni del layer `layer` siano tutti positivi
+    fn check_extra_signs(layer: usize, weights: &[Vec<f64>]) -> Result<(), BuildError> {
+        for (neuron, row) in weights.iter().enumerate() {
+            if let Some((source, &value)) = row.iter().enumerate().find(|(_, &w)| w < 0.0) {
+                return Err(BuildError::NegativeExtraWeight { layer, neuron, source, value });
+            }
+        }
+        Ok(())
+    }
+/// Controlla che i pesi interni del layer `layer` siano tutti negativi, e nulli sulla diagonale
+    fn check_intra_signs(layer: usize, weights: &[Vec<f64>]) -> Result<(), BuildError> {
+        for (neuron, row) in weights.iter().enumerate() {
+            if let Some(&value) = row.get(neuron).filter(|&&w| w != 0.0) {
+                return Err(BuildError::NonZeroIntraDiagonal { layer, neuron, value });
+            }
+            if let Some((source, &value)) = row.iter().enumerate().find(|(_, &w)| w > 0.0) {
+                return Err(BuildError::PositiveIntraWeight { layer, neuron, source, value });
+            }
+        }
+        Ok(())
     }
 /// Funzione per la scelta casuale di un layer e di un neurone all'interno di quest'ultimo.
 /// Ritorna una tupla contenente gli indici di layer e neurone
-    fn choose_neuron(neurons: &Vec<Vec<N>>, rng: &mut ThreadRng) -> (usize,usize){
+    fn choose_neuron(neurons: &[Vec<N>], rng: &mut StdRng) -> (usize,usize){
         let n_layers = rng.gen_range(0..neurons.len());
         let n_neuron = rng.gen_range(0..neurons[n_layers].len());
 
         (n_layers,n_neuron)
     }
+/// Ritorna gli indici (layer, neurone) di tutti i neuroni della rete
+    fn neuron_indices(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.params.neurons.iter().enumerate()
+            .flat_map(|(layer_index, layer)| (0..layer.len()).map(move |neuron_index| (layer_index, neuron_index)))
+    }
+/// Ritorna `true` se il componente `component` è utilizzato dal neurone `neuron_index` del layer `layer_index`,
+/// i.e. se un errore sul componente può avere effetto (e.g. la variabile ausiliaria esiste solo in alcuni modelli,
+/// l'unità esponenziale non viene utilizzata dall'integrazione di Eulero, i pesi intra layer di un neurone
+/// sono solo quelli verso gli altri neuroni del layer)
+    fn supports(&self, component: i32, layer_index: usize, neuron_index: usize) -> bool {
+        let neuron = &self.params.neurons[layer_index][neuron_index];
+        match component {
+            3 => self.params.neurons[layer_index].len() > 1,
+            6 | 7 => neuron.uses_multiplier(),
+            9 => neuron.has_aux(),
+            10 | 11 => neuron.uses_exp_unit(),
+            _ => true,
+        }
+    }
+/// Ritorna i blocchi elaborativi dell'unità assegnata al neurone `neuron_index` del layer `layer_index`,
+/// creati senza errori alla prima iniezione di un errore permanente sull'unità
+    fn unit_blocks(&mut self, layer_index: usize, neuron_index: usize) -> &mut ArithmeticUnits {
+        let unit = self.unit_index(layer_index, neuron_index);
+        self.faulty_units.entry(unit).or_insert(self.units)
+    }
 /// Funzione che ritorna un indice casuale in un vettore di pesi
-    fn weight_index(weights: &Vec<f64>, rng: &mut ThreadRng) -> usize{
+    fn weight_index(weights: &[f64], rng: &mut StdRng) -> usize{
         rng.gen_range(0..weights.len())
     }
+/// Funzione che ritorna un indice casuale nel vettore dei pesi intra layer del neurone `neuron_index`,
+/// escluso il peso sulla diagonale (sempre nullo e mai utilizzato dal layer)
+    fn intra_weight_index(weights: &[f64], neuron_index: usize, rng: &mut StdRng) -> usize{
+        let index = rng.gen_range(0..weights.len() - 1);
+        if index >= neuron_index { index + 1 } else { index }
+    }
 /// Funzione per generare a caso la presenza di un errore su uno solo o entrambi gli ingressi di un blocco elaborativo
-    fn generate_input_error(rng: &mut ThreadRng, error_type:i32)->(i32,i32){
+    fn generate_input_error(rng: &mut StdRng, error_type:i32)->(i32,i32){
         let index = rng.gen_range(0..3);
         match index{
             /* solo sul primo ingresso */
@@ -126,7 +560,8 @@ impl <N: Neuron+ Clone+Debug> SnnBuilder<N> {
     }
 
 
-/// Funzione per gestire l'iniezione di errori all'interno della rete
+/// Funzione per la scelta casuale dell'insieme di errori da iniettare nella rete: vengono scelti
+/// `fault_count` errori, ciascuno su un componente scelto casualmente tra `components`
 /// # Argomenti
 /// * `components` - lista di valori per indicare i possibili componenti in cui iniettare l'errore.
 ///   Possibili valori:
@@ -138,86 +573,338 @@ impl <N: Neuron+ Clone+Debug> SnnBuilder<N> {
 ///     - `5` -> Adder input
 ///     - `6` -> Multiplier output
 ///     - `7` -> Multiplier input
-/// * `error_type` - tipo di errore da iniettare nella rete:
+///     - `8` -> Contatore del periodo refrattario
+///     - `9` -> Variabile di stato ausiliaria del neurone
+///     - `10` -> Exp unit output
+///     - `11` -> Exp unit input
+///     - `12` -> Comparator output
+/// * `error_type` - tipo di errore da iniettare nella rete (se non sono stati settati più tipi con `set_error_types()`):
 ///     - `0` -> Stuck-at-0
 ///     - `1` -> Stuck-at-1
 ///     - `2` -> Transient bit-flip
+///
+/// * `rng` - generatore di numeri casuali con cui vengono scelti gli errori
+///
+/// Gli errori dell'insieme non colpiscono mai lo stesso registro di un neurone o lo stesso blocco elaborativo di un'unità,
+/// e solo i componenti utilizzati dal modello di neurone vengono scelti (e.g. la variabile ausiliaria viene ignorata
+/// per i neuroni LIF); se nessuno dei componenti è utilizzato ritorna `BuildError::UnsupportedComponents`
+    fn draw_faults(&self, components: &[i32], error_type: i32, rng: &mut StdRng) -> Result<Vec<Fault>, BuildError>{
+        let mut faults = vec![];
+        if components.is_empty() {return Ok(faults);}
+        /* i componenti non utilizzati da alcun neurone della rete vengono ignorati */
+        let selected = components;
+        let components: Vec<i32> = selected.iter().copied()
+            .filter(|&component| !self.candidate_masks(component).is_empty()
+                && self.neuron_indices().any(|(layer, neuron)| self.supports(component, layer, neuron)))
+            .collect();
+        if components.is_empty() {
+            return Err(BuildError::UnsupportedComponents { components: selected.to_vec() });
+        }
+        /* registri dei neuroni (layer, neurone, componente, peso) e blocchi elaborativi (unità, blocco) già colpiti */
+        let mut used_registers: Vec<(usize, usize, i32, Option<usize>)> = vec![];
+        let mut used_blocks: Vec<(usize, i32)> = vec![];
+        for placed in 0..self.fault_count {
+            /* scelta casuale di componente e neurone, ripetuta finché l'errore non si sovrappone a uno già scelto */
+            let mut attempts = 0;
+            let (component, layer_index, neuron_index, weight) = loop {
+                if attempts == MAX_PLACEMENT_ATTEMPTS {
+                    return Err(BuildError::FaultSetTooLarge { requested: self.fault_count, placed });
+                }
+                attempts += 1;
+                let component = components[rng.gen_range(0..components.len())];
+                let (layer_index, neuron_index) = SnnBuilder::choose_neuron(&self.params.neurons, rng);
+                if !self.supports(component, layer_index, neuron_index) {
+                    continue;
+                }
+                /* per i pesi, scelta casuale del peso colpito */
+                let weight = match component {
+                    2 => Some(SnnBuilder::<N>::weight_index(&self.params.extra_weights[layer_index][neuron_index], rng)),
+                    3 => Some(SnnBuilder::<N>::intra_weight_index(&self.params.intra_weights[layer_index][neuron_index], neuron_index, rng)),
+                    _ => None,
+                };
+                let overlaps = match component {
+                    /* ingresso e uscita appartengono allo stesso blocco elaborativo */
+                    4..=7 | 10..=12 => used_blocks.contains(&(self.unit_index(layer_index, neuron_index), component_block(component))),
+                    /* un registro (soglia, potenziale, singolo peso, ...) può essere forzato da un solo errore */
+                    _ => used_registers.contains(&(layer_index, neuron_index, component, weight)),
+                };
+                if !overlaps {
+                    break (component, layer_index, neuron_index, weight);
+                }
+            };
+            if matches!(component, 4..=7 | 10..=12) {
+                used_blocks.push((self.unit_index(layer_index, neuron_index), component_block(component)));
+            } else {
+                used_registers.push((layer_index, neuron_index, component, weight));
+            }
+            let error_type = match self.error_types.is_empty() {
+                true => error_type,
+                false => self.error_types[rng.gen_range(0..self.error_types.len())],
+            };
+            /* scelta casuale dei bit affetti */
+            let masks = self.candidate_masks(component);
+            let input_errors = match component {
+                /* l'unità esponenziale ha un solo ingresso */
+                11 => (error_type, 3),
+                _ => SnnBuilder::<N>::generate_input_error(rng, error_type)
+            };
+            faults.push(Fault { component, error_type, layer: layer_index, neuron: neuron_index, weight,
+                mask: masks[rng.gen_range(0..masks.len())], instant: None, input_errors });
+        }
+        Ok(faults)
+    }
+/// Ritorna il numero di bit del registro colpito da un errore sul componente `component`
+    fn component_width(&self, component: i32) -> u8 {
+        match component {
+            8 => REFRACTORY_WIDTH,
+            12 => 1,
+            _ => self.format.width(),
+        }
+    }
+/// Ritorna tutte le maschere di bit che un errore sul componente `component` può assumere,
+/// secondo il formato numerico, la forma degli errori e la restrizione sui campi del builder
+/// (vuoto se l'errore non è contenuto nel registro, e.g. più di `REFRACTORY_WIDTH` bit adiacenti sul contatore refrattario)
+    fn candidate_masks(&self, component: i32) -> Vec<u64> {
+        let width = self.component_width(component);
+        match (component, self.upset) {
+            /* l'uscita del comparatore è un singolo bit */
+            (12, _) => vec![1],
+            (_, upset) if !upset.fits(width) => vec![],
+            /* la maschera esplicita colpisce almeno un bit dei campi scelti, controllato da `check_dimensions()` */
+            (_, Upset::Mask(mask)) => vec![mask],
+            /* il bit meno significativo colpito viene scelto in modo che l'errore sia contenuto nella parola;
+                il contatore refrattario è un intero, senza campi */
+            (8, upset) => (0..=width - upset.span()).map(|start| upset.mask(start)).collect(),
+            /* con la restrizione sui campi, l'errore deve colpire almeno un bit di uno dei campi scelti */
+            (_, upset) => (0..=width - upset.span())
+                .filter(|&start| self.fields.is_empty()
+                    || (start..start + upset.span()).any(|bit| self.fields.contains(&self.format.field_of(bit))))
+                .map(|start| upset.mask(start))
+                .collect(),
+        }
+    }
+/// Ritorna la lista completa degli errori iniettabili nei componenti selezionati, per una campagna esaustiva:
+/// ogni maschera di bit valida di ogni componente, per tutti i layer e neuroni (per i blocchi elaborativi,
+/// per tutte le unità) e per ogni peso. Vengono esclusi i componenti che il neurone (o, per i blocchi elaborativi,
+/// nessun neurone dell'unità) non utilizza, secondo il modello e lo schema di integrazione: e.g. la variabile ausiliaria
+/// dei neuroni LIF o l'unità esponenziale con l'integrazione di Eulero.
+/// La lista è sempre la stessa per la stessa rete e configurazione del builder
+/// # Argomenti
+/// * `components` - lista dei componenti in cui iniettare gli errori (stessi valori di `build()`)
+/// * `error_type` - tipo di errore da iniettare (stessi valori di `build()`); se sono stati settati più tipi
+///   con `set_error_types()` vengono enumerati tutti
+/// * `instants` - durata dell'input della rete: gli errori transitori vengono enumerati su ciascuno dei suoi istanti,
+///   mentre gli errori intermittenti si attivano comunque in istanti casuali
+/// # Errori
+/// Ritorna un `BuildError` negli stessi casi di `check_dimensions()` (e.g. errore multi-bit non contenuto in una parola
+/// del formato) e `BuildError::UnsupportedComponents` se nessuno dei componenti è utilizzato dai neuroni della rete
+    pub fn exhaustive_faults(&self, components: &[i32], error_type: i32, instants: u64) -> Result<Vec<Fault>, BuildError> {
+        self.check_dimensions()?;
+        let error_types = match self.error_types.is_empty() {
+            true => vec![error_type],
+            false => self.error_types.clone(),
+        };
+        let all_neurons: Vec<(usize, usize)> = self.neuron_indices().collect();
+
+        let mut faults = vec![];
+        for &component in components {
+            let masks = self.candidate_masks(component);
+            let mut targets: Vec<(usize, usize)> = all_neurons.iter().copied()
+                .filter(|&(layer, neuron)| self.supports(component, layer, neuron))
+                .collect();
+            if matches!(component, 4..=7 | 10..=12) {
+                /* per ciascun blocco elaborativo basta un errore per unità: si sceglie il primo neurone
+                    assegnato all'unità che utilizza il blocco */
+                let mut units: Vec<usize> = vec![];
+                targets.retain(|&(layer, neuron)| {
+                    let unit = self.unit_index(layer, neuron);
+                    let first = !units.contains(&unit);
+                    units.push(unit);
+                    first
+                });
+            }
+            for &error_type in error_types.iter() {
+                let input_selectors = match component {
+                    5 | 7 => vec![(error_type, 3), (3, error_type), (error_type, error_type)],
+                    11 => vec![(error_type, 3)],
+                    _ => vec![(3, 3)],
+                };
+                let start_instants: Vec<Option<u64>> = match error_type {
+                    2 if self.intermittent.is_none() => (0..instants).map(Some).collect(),
+                    _ => vec![None],
+                };
+                for &(layer, neuron) in targets.iter() {
+                    let weights: Vec<Option<usize>> = match component {
+                        2 => (0..self.params.extra_weights[layer][neuron].len()).map(Some).collect(),
+                        /* il peso sulla diagonale non viene mai utilizzato dal layer */
+                        3 => (0..self.params.intra_weights[layer][neuron].len()).filter(|&index| index != neuron).map(Some).collect(),
+                        _ => vec![None],
+                    };
+                    for &weight in weights.iter() {
+                        for &mask in masks.iter() {
+                            for &input_errors in input_selectors.iter() {
+                                for &instant in start_instants.iter() {
+                                    faults.push(Fault { component, error_type, layer, neuron, weight, mask, instant, input_errors });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if faults.is_empty() && !components.is_empty() {
+            return Err(BuildError::UnsupportedComponents { components: components.to_vec() });
+        }
+        Ok(faults)
+    }
+/// Funzione per gestire l'iniezione dell'insieme di errori all'interno della rete
+/// # Argomenti
+/// * `faults` - errori da iniettare, scelti casualmente da `draw_faults()` o forniti dall'utente
 /// * `info_table` - struttura per salvare le informazioni di tutti gli errori inseriti
-/// * `transient_error` - variabile opzionale per salvare le informazioni temporanee di un errore transitorio
-    fn handle_errors(&mut self, components: &Vec<i32>, error_type: i32, info_table: &mut InfoTable, transient_error: &mut Option<(usize, usize, i32, u8,(i32,i32))>){
-        let mut rng = rand::thread_rng();
-        if components.len()==0 {return;}
-        let component_index = rng.gen_range(0..components.len());
-        /* scelta casuale di uno dei componenti */
-        let component = components[component_index];
-        let position: u8 = rng.gen_range(0..64);
+///
+/// Ritorna, per ciascun errore dell'insieme, le informazioni temporanee dell'errore transitorio o intermittente
+/// (`None` per gli errori permanenti, già iniettati nei parametri della rete o nei blocchi elaborativi)
+/// e il valore originale e corrotto del parametro colpito, se già noti alla costruzione
+    fn handle_errors(&mut self, faults: &[Fault], info_table: &mut InfoTable)
+        -> (Vec<Option<TransientErrorInfo>>, FaultValues){
+        faults.iter().map(|fault| {
+            let original = self.parameter_value(fault);
+            let transient_error = self.inject_error(fault, info_table);
+            /* alla costruzione sono noti solo i valori dei parametri colpiti da errori permanenti */
+            let values = match transient_error {
+                Some(_) => None,
+                None => original.zip(self.parameter_value(fault)),
+            };
+            (transient_error, values)
+        }).unzip()
+    }
+/// Ritorna il valore, nel formato del datapath, del parametro costante (soglia o peso) colpito dall'errore,
+/// `None` per gli altri componenti
+    fn parameter_value(&self, fault: &Fault) -> Option<f64> {
+        let value = match (fault.component, fault.weight) {
+            (0, _) => self.params.neurons[fault.layer][fault.neuron].get_th(),
+            (2, Some(index)) => self.params.extra_weights[fault.layer][fault.neuron][index],
+            (3, Some(index)) => self.params.intra_weights[fault.layer][fault.neuron][index],
+            _ => return None,
+        };
+        Some(self.format.quantize(value))
+    }
+/// Inietta un singolo errore dell'insieme sul componente del neurone scelto (o sull'unità
+/// di blocchi elaborativi a esso assegnata), salvandone le informazioni in `info_table`.
+/// Ritorna le informazioni temporanee dell'errore se transitorio o intermittente, `None` altrimenti
+    fn inject_error(&mut self, fault: &Fault, info_table: &mut InfoTable) -> Option<TransientErrorInfo>{
+        let Fault { component, error_type, layer: layer_index, neuron: neuron_index, weight, mask, instant, input_errors } = *fault;
+        let (err_input1, err_input2) = input_errors;
+        /* campo colpito dall'errore (quello del bit più significativo affetto), non significativo per registri interi e bit singoli */
+        let field = match component {
+            8 | 12 => None,
+            _ => Some(self.format.field_of(63 - mask.leading_zeros() as u8)),
+        };
 
+        info_table.add_fault(*fault);
         info_table.add_component(component as usize);
         info_table.add_error_type(error_type as usize);
-        info_table.add_bit(position as usize);
+        info_table.add_mask(mask);
+        info_table.add_field(field);
 
-        let (layer_index, neuron_index) = SnnBuilder::choose_neuron(&self.params.neurons.clone(),&mut rng);
         info_table.add_layer(layer_index);
         info_table.add_neuron(neuron_index);
+        info_table.add_weight(weight);
+        /* un errore su un blocco elaborativo colpisce l'unità assegnata al neurone scelto */
+        let unit = match component {
+            4..=7 | 10..=12 => Some(self.unit_index(layer_index, neuron_index)),
+            _ => None,
+        };
+        info_table.add_unit(unit);
 
-        let (err_input1, err_input2) = SnnBuilder::<N>::generate_input_error(&mut rng, error_type);
         /* In base al tipo di errore e componente selezionato, si possono verificare tre casi generali:
             1- stuck-at-X su parametri costanti (i.e. soglia e pesi): Il bit deve essere settato solo all'inizio
             2- stuck-at-X su membrana: deve essere garantito X ad ogni variazione del valore (i.e. ogni volta che il neurone processa un input)
             3- transient-bit-flip su qualsiasi componente: valore settato una volta sola, ma ad un istante casuale (verrà iniettato da Snn.process())
+            Un errore intermittente di qualsiasi tipo viene invece iniettato da Snn.process() negli istanti in cui si attiva
         */
 
         info_table.add_error_inputs(err_input1,err_input2);
         match (component,error_type) {
+            //intermittent error
+            _ if self.intermittent.is_some() => {
+                return Some(TransientErrorInfo { layer: layer_index, neuron: neuron_index, unit, component, weight, error_type, mask,
+                    input_errors, duration: 1, probability: self.intermittent, instant: None });
+            },
             //stuck_at_X on threshold
             (0,0)|(0,1)=>{
-                error_handling::threshold_fault(&mut self.params.neurons[layer_index][neuron_index], error_type, position);
+                error_handling::threshold_fault(&mut self.params.neurons[layer_index][neuron_index], error_type, mask, self.format);
 
             },
             //stuck-at-X on membrane
             (1,0)|(1,1)=>{
-                error_handling::membrane_fault(&mut self.params.neurons[layer_index][neuron_index], error_type, position);
+                error_handling::membrane_fault(&mut self.params.neurons[layer_index][neuron_index], error_type, mask, self.format);
 
             },
             //stuck-at-X on extra-weights
             (2,0)|(2,1)=>{
-                let w=&mut self.params.extra_weights[layer_index][neuron_index];
-                let idx=SnnBuilder::<N>::weight_index(w, &mut rng);
-                error_handling::weight_fault(&mut w[idx],error_type,position);
+                let idx = weight.expect("Weight faults always target a weight index");
+                error_handling::weight_fault(&mut self.params.extra_weights[layer_index][neuron_index][idx],error_type,mask,self.format);
 
             },
             //stuck-at-X on intra-weights
             (3,0)|(3,1)=>{
-                let w=&mut self.params.intra_weights[layer_index][neuron_index];
-                let idx=SnnBuilder::<N>::weight_index(w, &mut rng);
-                error_handling::weight_fault(&mut w[idx],error_type,position);
+                let idx = weight.expect("Weight faults always target a weight index");
+                error_handling::weight_fault(&mut self.params.intra_weights[layer_index][neuron_index][idx],error_type,mask,self.format);
 
             },
             //transient error
-            (0,2)|(1,2)|(2,2)|(3,2)|(4,2)|(5,2)|(6,2)|(7,2)=> {
-                *transient_error = Some((layer_index, neuron_index, component, position, (err_input1, err_input2)));
+            (0,2)|(1,2)|(2,2)|(3,2)|(4,2)|(5,2)|(6,2)|(7,2)|(8,2)|(9,2)|(10,2)|(11,2)|(12,2)=> {
+                return Some(TransientErrorInfo { layer: layer_index, neuron: neuron_index, unit, component, weight, error_type, mask,
+                    input_errors, duration: self.transient_duration, probability: None, instant });
             },
             //stuck_at_X on Adder output
-            (4,0)|(4,1)=>{self.adder.set_params(error_type, position);
+            (4,0)|(4,1)=>{self.unit_blocks(layer_index, neuron_index).adder.set_params(error_type, mask);
 
             }
             //stuck_at_X on Adder input(s)
             (5,0)|(5,1)=>{
-                self.adder.set_params_input(position,err_input1,err_input2);
+                self.unit_blocks(layer_index, neuron_index).adder.set_params_input(mask,err_input1,err_input2);
 
             },
             //stuck_at_X on Multiplier output
             (6,0)|(6,1)=>{
-                self.mult.set_params(error_type,position);
+                self.unit_blocks(layer_index, neuron_index).mult.set_params(error_type,mask);
 
             },
             //stuck_at_X on Multiplier input(s)
             (7,0)|(7,1)=>{
-                self.mult.set_params_input(position,err_input1,err_input2);
+                self.unit_blocks(layer_index, neuron_index).mult.set_params_input(mask,err_input1,err_input2);
+
+            },
+            //stuck-at-X on refractory counter
+            (8,0)|(8,1)=>{
+                error_handling::refractory_fault(&mut self.params.neurons[layer_index][neuron_index], error_type, mask);
+
+            },
+            //stuck-at-X on auxiliary state variable
+            (9,0)|(9,1)=>{
+                error_handling::aux_fault(&mut self.params.neurons[layer_index][neuron_index], error_type, mask, self.format);
+
+            },
+            //stuck_at_X on Exp unit output
+            (10,0)|(10,1)=>{
+                self.unit_blocks(layer_index, neuron_index).exp_unit.set_params(error_type,mask);
+
+            },
+            //stuck_at_X on Exp unit input
+            (11,0)|(11,1)=>{
+                self.unit_blocks(layer_index, neuron_index).exp_unit.set_params_input(mask,err_input1);
+
+            },
+            //stuck_at_X on Comparator output
+            (12,0)|(12,1)=>{
+                self.unit_blocks(layer_index, neuron_index).cmp.set_params(error_type);
 
             }
             (_,_)=>{}
         }
+        None
     }
 /// Funzione che crea la rete SNN dai parametri di costruzione
 /// # Argomenti
@@ -231,35 +918,454 @@ impl <N: Neuron+ Clone+Debug> SnnBuilder<N> {
 ///     - `5` -> Adder input
 ///     - `6` -> Multiplier output
 ///     - `7` -> Multiplier input
+///     - `8` -> Contatore del periodo refrattario
+///     - `9` -> Variabile di stato ausiliaria del neurone
+///     - `10` -> Exp unit output
+///     - `11` -> Exp unit input
+///     - `12` -> Comparator output
 /// * `error_type` - tipo di errore da iniettare nella rete:
 ///     - `0` -> Stuck-at-0
 ///     - `1` -> Stuck-at-1
 ///     - `2` -> Transient bit-flip
 /// * `info_table` - struttura per salvare le informazioni di tutti gli errori inseriti
-    pub fn build<const INPUT_DIM: usize, const OUTPUT_DIM:usize>(&mut self, components: &Vec<i32>, error_type: i32,
-                                                                 info_table: &mut InfoTable) -> SNN<N, { INPUT_DIM }, { OUTPUT_DIM }>{
-        if self.params.extra_weights.len() != self.params.neurons.len() || self.params.intra_weights.len() != self.params.neurons.len(){
-            panic!("Wrong number bewteen layers!")
+/// # Errori
+/// Ritorna un `BuildError` se le dimensioni dei layer non sono coerenti tra loro o con `INPUT_DIM` e `OUTPUT_DIM`,
+/// oppure se il segno di uno dei pesi non è corretto,
+/// se nessun bit del formato appartiene ai campi selezionati con `set_fields()`
+/// o se l'errore multi-bit scelto con `set_upset()` non è contenuto in una parola del formato;
+/// ritorna `BuildError::FaultSetTooLarge` se gli errori dell'insieme non possono essere posizionati senza sovrapporsi
+/// e `BuildError::UnsupportedComponents` se nessuno dei componenti è utilizzato dai neuroni della rete
+    pub fn build<const INPUT_DIM: usize, const OUTPUT_DIM:usize>(&mut self, components: &[i32], error_type: i32,
+                                                                 info_table: &mut InfoTable) -> Result<SNN<N, { INPUT_DIM }, { OUTPUT_DIM }>, BuildError>{
+        self.check_dimensions()?;
+        /* gli errori vengono scelti con una copia del generatore: la rete riceve lo stesso generatore che riceverebbe
+            ripetendo gli stessi errori con `build_with_faults()`, e quindi gli stessi istanti di attivazione */
+        let faults = self.draw_faults(components, error_type, &mut self.rng.clone())?;
+        self.build_with_faults(&faults, info_table)
+    }
+/// Funzione che crea la rete SNN iniettando esattamente gli errori specificati (e.g. quelli di una campagna
+/// esaustiva, ritornati da `exhaustive_faults()`), invece di sceglierli casualmente
+/// # Argomenti
+/// * `faults` - errori da iniettare nella rete
+/// * `info_table` - struttura per salvare le informazioni di tutti gli errori inseriti
+/// # Errori
+/// Ritorna un `BuildError` negli stessi casi di `build()`;
+/// ritorna `BuildError::InvalidFault` se uno degli errori non colpisce un componente esistente della rete
+    pub fn build_with_faults<const INPUT_DIM: usize, const OUTPUT_DIM:usize>(&mut self, faults: &[Fault],
+                                                                 info_table: &mut InfoTable) -> Result<SNN<N, { INPUT_DIM }, { OUTPUT_DIM }>, BuildError>{
+        let (input_dim, output_dim) = self.check_dimensions()?;
+        self.check_faults(faults)?;
+        if input_dim != INPUT_DIM {
+            return Err(BuildError::InputDimMismatch { expected: INPUT_DIM, found: input_dim });
+        }
+        if output_dim != OUTPUT_DIM {
+            return Err(BuildError::OutputDimMismatch { expected: OUTPUT_DIM, found: output_dim });
+        }
+
+        let (layers, transient_errors, values) = self.create_layers(faults, info_table);
+        Ok(SNN::<N, {INPUT_DIM }, { OUTPUT_DIM }>::new(layers, transient_errors, values, self.faulty_units.clone(), self.network_rng()))
+    }
+/// Funzione che crea una rete `DynSNN`, le cui dimensioni di input e output sono ricavate
+/// a *runtime* dai parametri di costruzione (e.g. rete caricata da file).
+/// Prima della costruzione viene controllata la coerenza delle dimensioni tra tutti i layer.
+/// # Argomenti
+/// Stessi argomenti di `build()`
+/// # Errori
+/// Ritorna un `BuildError` se le dimensioni dei layer non sono coerenti tra loro
+/// oppure se il segno di uno dei pesi non è corretto,
+/// se nessun bit del formato appartiene ai campi selezionati con `set_fields()`
+/// o se l'errore multi-bit scelto con `set_upset()` non è contenuto in una parola del formato;
+/// ritorna `BuildError::FaultSetTooLarge` se gli errori dell'insieme non possono essere posizionati senza sovrapporsi
+/// e `BuildError::UnsupportedComponents` se nessuno dei componenti è utilizzato dai neuroni della rete
+    pub fn build_dyn(&mut self, components: &[i32], error_type: i32, info_table: &mut InfoTable) -> Result<DynSNN<N>, BuildError>{
+        self.check_dimensions()?;
+        /* gli errori vengono scelti con una copia del generatore: la rete riceve lo stesso generatore che riceverebbe
+            ripetendo gli stessi errori con `build_with_faults()`, e quindi gli stessi istanti di attivazione */
+        let faults = self.draw_faults(components, error_type, &mut self.rng.clone())?;
+        self.build_dyn_with_faults(&faults, info_table)
+    }
+/// Funzione che crea una rete `DynSNN` iniettando esattamente gli errori specificati, invece di sceglierli casualmente
+/// # Argomenti
+/// Stessi argomenti di `build_with_faults()`
+/// # Errori
+/// Ritorna un `BuildError` negli stessi casi di `build_dyn()`
+    pub fn build_dyn_with_faults(&mut self, faults: &[Fault], info_table: &mut InfoTable) -> Result<DynSNN<N>, BuildError>{
+        let (input_dim, output_dim) = self.check_dimensions()?;
+        self.check_faults(faults)?;
+
+        let (layers, transient_errors, values) = self.create_layers(faults, info_table);
+        Ok(DynSNN::new(layers, input_dim, output_dim, transient_errors, values, self.faulty_units.clone(), self.network_rng()))
+    }
+/// Controlla che le dimensioni di neuroni, pesi esterni e pesi interni siano coerenti tra loro
+/// e che i pesi abbiano il segno corretto (i parametri potrebbero essere stati letti da file),
+/// oltre alla compatibilità con il formato numerico dei campi a cui è ristretta l'iniezione e della forma degli errori;
+/// ritorna la dimensione dell'input e dell'output della rete
+    fn check_dimensions(&self) -> Result<(usize, usize), BuildError>{
+        if !self.format.is_valid() {
+            return Err(BuildError::InvalidFormat { format: self.format });
+        }
+        if !self.fields.is_empty() && self.format.positions_in(&self.fields).is_empty() {
+            return Err(BuildError::NoBitsInFields { format: self.format, fields: self.fields.clone() });
+        }
+        if !self.upset.fits(self.format.width()) {
+            return Err(BuildError::InvalidUpset { upset: self.upset, width: self.format.width() });
+        }
+        if let Upset::Mask(mask) = self.upset {
+            if !self.fields.is_empty() && !self.format.positions_in(&self.fields).iter().any(|&bit| mask >> bit & 1 == 1) {
+                return Err(BuildError::MaskOutsideFields { mask, fields: self.fields.clone() });
+            }
+        }
+        let neurons = &self.params.neurons;
+        if neurons.is_empty() {
+            return Err(BuildError::EmptyNetwork);
+        }
+        if self.params.extra_weights.len() != neurons.len() || self.params.intra_weights.len() != neurons.len(){
+            return Err(BuildError::LayerCountMismatch {
+                neurons: neurons.len(),
+                extra_weights: self.params.extra_weights.len(),
+                intra_weights: self.params.intra_weights.len(),
+            });
+        }
+        /* la dimensione dell'input è data dal numero di colonne dei pesi esterni del primo layer */
+        let input_dim = self.params.extra_weights[0].first().map(|row| row.len()).unwrap_or(0);
+        let mut previous_dim = input_dim;
+        for (l_index, layer) in neurons.iter().enumerate() {
+            if layer.is_empty() {
+                return Err(BuildError::EmptyLayer { layer: l_index });
+            }
+            let extra = &self.params.extra_weights[l_index];
+            let intra = &self.params.intra_weights[l_index];
+            SnnBuilder::<N>::check_shape(l_index, WeightMatrix::Extra, extra, (layer.len(), previous_dim))?;
+            SnnBuilder::<N>::check_shape(l_index, WeightMatrix::Intra, intra, (layer.len(), layer.len()))?;
+            SnnBuilder::<N>::check_extra_signs(l_index, extra)?;
+            SnnBuilder::<N>::check_intra_signs(l_index, intra)?;
+            previous_dim = layer.len();
         }
 
-        let mut layers: Vec<Arc<Mutex<Layer<N>>>> = Vec::new();
-        let mut transient: Option<(usize, usize,i32, u8, (i32,i32))>=None;
-        self.handle_errors(components,error_type,info_table,&mut transient);
+        Ok((input_dim, previous_dim))
+    }
+/// Controlla che ciascun errore dell'insieme colpisca un componente esistente della rete, utilizzato dal neurone colpito,
+/// e che inizi entro la durata dell'input, se nota
+/// (gli errori potrebbero essere stati letti da file e la rete o l'input potrebbero essere cambiati)
+    fn check_faults(&self, faults: &[Fault]) -> Result<(), BuildError>{
+        for (index, fault) in faults.iter().enumerate() {
+            let Some(layer) = self.params.neurons.get(fault.layer) else {
+                return Err(BuildError::InvalidFault { index, fault: *fault });
+            };
+            let weights = match fault.component {
+                2 => self.params.extra_weights[fault.layer].get(fault.neuron).map(|row| row.len()),
+                3 => self.params.intra_weights[fault.layer].get(fault.neuron).map(|row| row.len()),
+                _ => None,
+            };
+            let valid_weight = match (fault.component, fault.weight) {
+                (2, Some(index)) => weights.is_some_and(|len| index < len),
+                (3, Some(index)) => weights.is_some_and(|len| index < len) && index != fault.neuron,
+                (2, None) | (3, None) => false,
+                _ => true,
+            };
+            let valid_inputs = [fault.input_errors.0, fault.input_errors.1].iter().all(|err| (0..=3).contains(err));
+            let valid = (0..=12).contains(&fault.component)
+                && (0..=2).contains(&fault.error_type)
+                && fault.neuron < layer.len()
+                && self.supports(fault.component, fault.layer, fault.neuron)
+                && fault.instant.is_none_or(|instant| self.input_length.is_none_or(|length| instant < length))
+                && valid_weight
+                && valid_inputs
+                && Upset::Mask(fault.mask).fits(self.component_width(fault.component));
+            if !valid {
+                return Err(BuildError::InvalidFault { index, fault: *fault });
+            }
+        }
+        Ok(())
+    }
+/// Inietta l'insieme di errori nei parametri della rete e crea i layer a partire da questi ultimi.
+/// Ritorna i layer creati e, per ciascun errore, l'eventuale errore transitorio ancora da iniettare
+/// e i valori originale e corrotto del parametro colpito, se noti alla costruzione
+    fn create_layers(&mut self, faults: &[Fault], info_table: &mut InfoTable)
+        -> (Layers<N>, Vec<Option<TransientErrorInfo>>, FaultValues){
+        let mut layers: Layers<N> = Vec::new();
+        let (transient_errors, values) = self.handle_errors(faults,info_table);
 
-        let mut n_iter = self.params.neurons.clone().into_iter();
+        let n_iter = self.params.neurons.clone().into_iter();
         let mut extra_iter = self.params.extra_weights.clone().into_iter();
         let mut intra_iter = self.params.intra_weights.clone().into_iter();
 
-        while let Some(layer) = n_iter.next() {
-            let new_extra_iter = extra_iter.next().unwrap();
-            let new_intra_iter = intra_iter.next().unwrap();
+        for (l_index, mut layer) in n_iter.enumerate() {
+            let mut new_extra_iter = extra_iter.next().unwrap();
+            let mut new_intra_iter = intra_iter.next().unwrap();
+            /* i parametri vengono memorizzati nel formato del datapath */
+            if self.format != NumericFormat::Float64 {
+                new_extra_iter.iter_mut().flatten().for_each(|w| *w = self.format.quantize(*w));
+                new_intra_iter.iter_mut().flatten().for_each(|w| *w = self.format.quantize(*w));
+                layer.iter_mut().for_each(|neuron| neuron.quantize(self.format));
+            }
+            /* unità di blocchi elaborativi assegnate ai neuroni del layer */
+            let units = (0..layer.len()).map(|n_index| self.unit_index(l_index, n_index)).collect();
 
             /* creazione di un nuovo layer */
-            let new_layer = Layer::new(layer, new_extra_iter, new_intra_iter);
+            let new_layer = Layer::new(layer, new_extra_iter, new_intra_iter, self.accumulation, units, self.format);
             layers.push(Arc::new(Mutex::new(new_layer)));
         }
-        SNN::<N, {INPUT_DIM }, { OUTPUT_DIM }>::new(layers, transient, self.adder.clone(), self.mult.clone())
+        (layers, transient_errors, values)
     }
 
 
-}
\ No newline at end of file
+}
+/// Ritorna il blocco elaborativo a cui appartiene il componente (ingresso e uscita dello stesso blocco coincidono)
+fn component_block(component: i32) -> i32 {
+    match component {
+        5 => 4,
+        7 => 6,
+        11 => 10,
+        _ => component,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::lifneuron::LIFNeuron;
+    use crate::error_handling::numeric_format::Overflow;
+
+    fn lif() -> LIFNeuron {
+        LIFNeuron::new(0.05, 0.0, 0.0, 1.0, 1.0)
+    }
+    /// Rete valida con input di dimensione 3: un layer di 3 neuroni e un layer di uscita di 1 neurone
+    fn network() -> SnnBuilder<LIFNeuron> {
+        let mut builder = SnnBuilder::new();
+        builder.add_layer().add_weight([
+            [0.1, 0.2, 0.3],
+            [0.4, 0.5, 0.6],
+            [0.7, 0.8, 0.9]
+        ]).unwrap().add_neurons([lif(), lif(), lif()]).unwrap().add_intra_weights([
+            [0.0, -0.1, -0.2],
+            [-0.3, 0.0, -0.4],
+            [-0.5, -0.6, 0.0]
+        ]).unwrap()
+        .add_layer().add_weight([[0.1, 0.2, 0.3]]).unwrap().add_neurons([lif()]).unwrap().add_intra_weights([[0.0]]).unwrap();
+        builder.set_seed(7);
+        builder
+    }
+    fn fault(component: i32, layer: usize, neuron: usize, weight: Option<usize>) -> Fault {
+        Fault { component, error_type: 1, layer, neuron, weight, mask: 1, instant: None, input_errors: (3, 3) }
+    }
+    fn build_error(builder: &mut SnnBuilder<LIFNeuron>, components: &[i32]) -> Option<BuildError> {
+        builder.build_dyn(components, 1, &mut InfoTable::new()).err()
+    }
+    fn fault_error(builder: &mut SnnBuilder<LIFNeuron>, faults: &[Fault]) -> Option<BuildError> {
+        builder.build_dyn_with_faults(faults, &mut InfoTable::new()).err()
+    }
+
+    #[test]
+    fn valid_network() {
+        assert_eq!(build_error(&mut network(), &[0, 1, 2, 3, 4, 5]), None);
+        assert!(network().build::<3, 1>(&[1], 2, &mut InfoTable::new()).is_ok());
+    }
+
+    #[test]
+    fn empty_network_and_layer() {
+        assert_eq!(build_error(&mut SnnBuilder::new(), &[]), Some(BuildError::EmptyNetwork));
+        let mut builder = SnnBuilder::<LIFNeuron>::new();
+        assert_eq!(builder.add_neurons::<0>([]).err(), Some(BuildError::EmptyLayer { layer: 0 }));
+    }
+
+    #[test]
+    fn layer_count_mismatch() {
+        let mut builder = SnnBuilder::new();
+        builder.add_layer().add_weight([[0.1, 0.2]]).unwrap().add_neurons([lif()]).unwrap();
+        assert_eq!(build_error(&mut builder, &[]),
+                   Some(BuildError::LayerCountMismatch { neurons: 1, extra_weights: 1, intra_weights: 0 }));
+    }
+
+    #[test]
+    fn wrong_extra_shape() {
+        /* righe diverse dal numero di neuroni del layer */
+        let mut builder = SnnBuilder::new();
+        builder.add_layer().add_neurons([lif(), lif()]).unwrap();
+        assert_eq!(builder.add_weight([[0.1; 3]; 3]).err(), Some(BuildError::WrongMatrixShape {
+            layer: 0, matrix: WeightMatrix::Extra, expected: (2, 3), found: (3, 3) }));
+        /* colonne diverse dal numero di neuroni del layer precedente */
+        let mut builder = network();
+        builder.add_layer();
+        assert_eq!(builder.add_weight([[0.1, 0.2]]).err(), Some(BuildError::WrongMatrixShape {
+            layer: 2, matrix: WeightMatrix::Extra, expected: (1, 1), found: (1, 2) }));
+        /* parametri letti da file: la forma viene controllata alla costruzione */
+        let params = SnnParams::new(vec![vec![lif(), lif()]], vec![vec![vec![0.1, 0.2], vec![0.3]]], vec![vec![vec![0.0, -0.1], vec![-0.1, 0.0]]]);
+        assert_eq!(build_error(&mut SnnBuilder::from_params(params), &[]), Some(BuildError::WrongMatrixShape {
+            layer: 0, matrix: WeightMatrix::Extra, expected: (2, 2), found: (2, 1) }));
+    }
+
+    #[test]
+    fn wrong_intra_shape() {
+        let mut builder = SnnBuilder::new();
+        builder.add_layer().add_neurons([lif(), lif()]).unwrap();
+        assert_eq!(builder.add_intra_weights([[0.0, -0.1, -0.1], [-0.1, 0.0, -0.1], [-0.1, -0.1, 0.0]]).err(),
+                   Some(BuildError::WrongMatrixShape { layer: 0, matrix: WeightMatrix::Intra, expected: (2, 2), found: (3, 3) }));
+    }
+
+    #[test]
+    fn weight_sign_errors() {
+        let mut builder = SnnBuilder::<LIFNeuron>::new();
+        assert_eq!(builder.add_weight([[0.1, -0.2]]).err(),
+                   Some(BuildError::NegativeExtraWeight { layer: 0, neuron: 0, source: 1, value: -0.2 }));
+        assert_eq!(builder.add_intra_weights([[0.0, -0.1], [0.3, 0.0]]).err(),
+                   Some(BuildError::PositiveIntraWeight { layer: 0, neuron: 1, source: 0, value: 0.3 }));
+        assert_eq!(builder.add_intra_weights([[0.0, -0.1], [-0.1, -0.5]]).err(),
+                   Some(BuildError::NonZeroIntraDiagonal { layer: 0, neuron: 1, value: -0.5 }));
+    }
+
+    #[test]
+    fn input_and_output_dimensions() {
+        assert_eq!(network().build::<4, 1>(&[], -1, &mut InfoTable::new()).err(),
+                   Some(BuildError::InputDimMismatch { expected: 4, found: 3 }));
+        assert_eq!(network().build::<3, 2>(&[], -1, &mut InfoTable::new()).err(),
+                   Some(BuildError::OutputDimMismatch { expected: 2, found: 1 }));
+    }
+
+    #[test]
+    fn injection_configuration_errors() {
+        let mut builder = network();
+        builder.set_format(NumericFormat::Fixed { int_bits: 40, frac_bits: 40, overflow: Overflow::Saturate });
+        assert!(matches!(build_error(&mut builder, &[1]), Some(BuildError::InvalidFormat { .. })));
+
+        let mut builder = network();
+        builder.set_format(NumericFormat::fixed(3, 12, Overflow::Saturate)).set_fields(&[BitField::Exponent]);
+        assert!(matches!(build_error(&mut builder, &[1]), Some(BuildError::NoBitsInFields { .. })));
+
+        let mut builder = network();
+        builder.set_upset(Upset::Adjacent(65));
+        assert_eq!(build_error(&mut builder, &[1]), Some(BuildError::InvalidUpset { upset: Upset::Adjacent(65), width: 64 }));
+
+        let mut builder = network();
+        builder.set_fields(&[BitField::Exponent]).set_upset(Upset::Mask(1));
+        assert_eq!(build_error(&mut builder, &[1]), Some(BuildError::MaskOutsideFields { mask: 1, fields: vec![BitField::Exponent] }));
+    }
+
+    #[test]
+    fn injection_setter_errors() {
+        let mut builder = network();
+        assert_eq!(builder.set_transient_duration(0).err(), Some(BuildError::ZeroTransientDuration));
+        assert_eq!(builder.set_fault_count(0).err(), Some(BuildError::EmptyFaultSet));
+        for probability in [0.0, -0.5, 1.5, f64::NAN] {
+            assert!(matches!(builder.set_intermittent(Some(probability)).err(), Some(BuildError::InvalidProbability { .. })));
+        }
+        assert_eq!(builder.set_error_types(&[0, 3]).err(), Some(BuildError::InvalidErrorTypes { error_types: vec![0, 3] }));
+        assert_eq!(builder.set_error_types(&[-1]).err(), Some(BuildError::InvalidErrorTypes { error_types: vec![-1] }));
+        /* i valori non validi non modificano il builder */
+        assert_eq!(builder.injection_config(), InjectionConfig::default());
+        /* valori limite validi; un vettore vuoto di tipi usa il tipo passato a `build()` */
+        assert!(builder.set_transient_duration(1).is_ok() && builder.set_fault_count(1).is_ok());
+        assert!(builder.set_intermittent(Some(1.0)).is_ok() && builder.set_intermittent(None).is_ok());
+        assert!(builder.set_error_types(&[]).is_ok());
+        /* la configurazione completa (e.g. letta da una lista di errori) viene controllata allo stesso modo */
+        let config = InjectionConfig { intermittent: Some(1.5), ..InjectionConfig::default() };
+        assert_eq!(builder.set_injection_config(&config).err(), Some(BuildError::InvalidProbability { probability: 1.5 }));
+        let config = InjectionConfig { transient_duration: 0, ..InjectionConfig::default() };
+        assert_eq!(builder.set_injection_config(&config).err(), Some(BuildError::ZeroTransientDuration));
+        let config = InjectionConfig { fault_count: 0, ..InjectionConfig::default() };
+        assert_eq!(builder.set_injection_config(&config).err(), Some(BuildError::EmptyFaultSet));
+        let config = InjectionConfig { error_types: vec![0, 5], ..InjectionConfig::default() };
+        assert_eq!(builder.set_injection_config(&config).err(), Some(BuildError::InvalidErrorTypes { error_types: vec![0, 5] }));
+        let config = InjectionConfig { error_types: vec![0, 2], ..InjectionConfig::default() };
+        assert_eq!(builder.set_injection_config(&config).unwrap().injection_config(), config);
+    }
+
+    #[test]
+    fn unsupported_components_and_fault_sets() {
+        /* il neurone LIF non ha una variabile ausiliaria */
+        assert_eq!(build_error(&mut network(), &[9]), Some(BuildError::UnsupportedComponents { components: vec![9] }));
+        /* quattro soglie distinte in una rete di quattro neuroni, non cinque */
+        let mut builder = network();
+        builder.set_fault_count(4).unwrap();
+        assert_eq!(build_error(&mut builder, &[0]), None);
+        builder.set_fault_count(5).unwrap();
+        assert_eq!(build_error(&mut builder, &[0]), Some(BuildError::FaultSetTooLarge { requested: 5, placed: 4 }));
+    }
+
+    #[test]
+    fn out_of_range_faults() {
+        let mut builder = network();
+        assert_eq!(fault_error(&mut builder, &[fault(1, 1, 0, None), fault(3, 0, 2, Some(1))]), None);
+        let invalid = [
+            /* layer, neurone e peso inesistenti */
+            fault(1, 2, 0, None),
+            fault(1, 1, 1, None),
+            fault(2, 1, 0, Some(3)),
+            fault(3, 0, 0, None),
+            /* componente e tipo di errore inesistenti */
+            fault(13, 0, 0, None),
+            Fault { error_type: 3, ..fault(1, 0, 0, None) },
+            /* maschera vuota o oltre la larghezza del registro */
+            Fault { mask: 0, ..fault(1, 0, 0, None) },
+            Fault { mask: 1 << 16, ..fault(8, 0, 0, None) },
+            Fault { mask: 2, ..fault(12, 0, 0, None) },
+            /* errori sugli ingressi non validi */
+            Fault { input_errors: (4, 3), ..fault(5, 0, 0, None) },
+        ];
+        for invalid_fault in invalid {
+            assert_eq!(fault_error(&mut builder, &[fault(1, 0, 0, None), invalid_fault]),
+                       Some(BuildError::InvalidFault { index: 1, fault: invalid_fault }));
+        }
+        let mut builder = network();
+        builder.set_format(NumericFormat::BFloat16);
+        let wide = Fault { mask: 1 << 16, ..fault(1, 0, 0, None) };
+        assert_eq!(fault_error(&mut builder, &[wide]), Some(BuildError::InvalidFault { index: 0, fault: wide }));
+    }
+
+    #[test]
+    fn replayed_faults_activate_at_the_same_instants() {
+        let input = vec![vec![1, 1, 1]; 30];
+        let mut builder = network();
+        builder.set_intermittent(Some(0.5)).unwrap().set_fault_count(3).unwrap();
+        let mut table = InfoTable::new();
+        let mut snn = builder.clone().build_dyn(&[0, 1, 2], 1, &mut table).unwrap();
+        let output = snn.process(&input).unwrap();
+        /* ripetendo gli stessi errori con lo stesso seme si ottengono le stesse attivazioni */
+        let faults = table.fault_list(builder.injection_config(), 7).runs[0].faults.clone();
+        let mut replay = builder.build_dyn_with_faults(&faults, &mut InfoTable::new()).unwrap();
+        assert_eq!(replay.process(&input), Ok(output));
+        assert_eq!(replay.fires(), snn.fires());
+        assert!(snn.fires().iter().any(|&fires| fires.is_some_and(|fires| fires > 0 && fires < 30)));
+    }
+
+    #[test]
+    fn fault_instant_within_the_input() {
+        let transient = |instant| Fault { error_type: 2, instant: Some(instant), ..fault(1, 0, 0, None) };
+        let mut builder = network();
+        /* senza durata dell'input l'istante non viene controllato */
+        assert_eq!(fault_error(&mut builder, &[transient(10)]), None);
+        builder.set_input_length(4);
+        assert_eq!(fault_error(&mut builder, &[transient(3)]), None);
+        assert_eq!(fault_error(&mut builder, &[transient(4)]), Some(BuildError::InvalidFault { index: 0, fault: transient(4) }));
+    }
+
+    #[test]
+    fn exhaustive_fault_count() {
+        let builder = network();
+        /* un errore per ciascuno dei 64 bit di un Float64 */
+        assert_eq!(builder.exhaustive_faults(&[0], 1, 0).unwrap().len(), 4 * 64);
+        assert_eq!(builder.exhaustive_faults(&[2], 1, 0).unwrap().len(), (3 * 3 + 3) * 64);
+        /* pesi intra layer: la diagonale è esclusa e il layer di uscita, con un solo neurone, non ne ha */
+        let intra = builder.exhaustive_faults(&[3], 1, 0).unwrap();
+        assert_eq!(intra.len(), 3 * 2 * 64);
+        assert!(intra.iter().all(|fault| fault.layer == 0 && fault.weight != Some(fault.neuron)));
+        assert_eq!(fault_error(&mut network(), &[fault(3, 0, 1, Some(1))]),
+                   Some(BuildError::InvalidFault { index: 0, fault: fault(3, 0, 1, Some(1)) }));
+        /* gli errori transitori vengono enumerati su ciascun istante dell'input */
+        assert_eq!(builder.exhaustive_faults(&[0], 2, 5).unwrap().len(), 4 * 64 * 5);
+    }
+
+    #[test]
+    fn protected_accumulation_is_quantized() {
+        /* in BFloat16 1 + 2^-8 viene arrotondato a 1: con le somme parziali arrotondate il potenziale resta 1,
+            mentre la somma esatta 1 + 2^-7 supererebbe la soglia */
+        for accumulation in [AccumulationMode::All, AccumulationMode::Protected] {
+            let mut builder = SnnBuilder::new();
+            builder.add_layer().add_weight([[1.0, 0.00390625, 0.00390625]]).unwrap()
+                .add_neurons([LIFNeuron::new(1.0, 0.0, 0.0, 1.0, 1.0)]).unwrap().add_intra_weights([[0.0]]).unwrap();
+            builder.set_format(NumericFormat::BFloat16).set_accumulation(accumulation);
+            let mut snn = builder.build_dyn(&[], -1, &mut InfoTable::new()).unwrap();
+            assert_eq!(snn.process(&[vec![1, 1, 1]]).unwrap(), vec![vec![0]], "{:?}", accumulation);
+        }
+    }
+}